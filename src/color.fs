@@ -0,0 +1,5 @@
+varying highp vec4 col;
+
+void main() {
+	gl_FragColor = vec4(col.rgb * col.a,col.a);
+}
@@ -0,0 +1,80 @@
+use wasm_bindgen::prelude::*;
+use std::fmt;
+
+//everything that can stop the game from getting to its first frame
+#[derive(Debug)]
+pub enum StartupError {
+	NoWindow,
+	NoDocument,
+	NoBody,
+	NoCanvas,
+	NoWebGl,
+	Shader { name: &'static str, log: String },
+	Link(String),
+	Texture(String),
+	Js(String), //anything else the browser threw at us
+}
+
+pub use self::StartupError::*;
+
+impl From<JsValue> for StartupError {
+	fn from(e: JsValue) -> Self {
+		Js(e.as_string().unwrap_or_else(|| format!("{:?}", e)))
+	}
+}
+
+impl fmt::Display for StartupError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			NoWindow => write!(f, "no global window exists"),
+			NoDocument => write!(f, "the window has no document"),
+			NoBody => write!(f, "the page has no body"),
+			NoCanvas => write!(f, "the page has no <canvas id=\"canvas\">"),
+			NoWebGl => write!(f, "your browser does not support WebGL, or it is disabled"),
+			Shader { name, log } => write!(f, "failed to compile shader {}:\n{}", name, log),
+			Link(log) => write!(f, "failed to link shader program:\n{}", log),
+			Texture(what) => write!(f, "failed to load textures: {}", what),
+			Js(e) => write!(f, "{}", e),
+		}
+	}
+}
+
+impl StartupError {
+	//missing textures still leave a playable game, sprites are drawn as flat colors instead
+	pub fn fatal(&self) -> bool {
+		match self {
+			Texture(_) => false,
+			_ => true,
+		}
+	}
+	
+	//puts the error on the page so players see something other than a black screen
+	//errors that aren't fatal go along the bottom and let clicks through so the game stays playable
+	pub fn show(&self) {
+		log!("{}", self);
+		let document = match web_sys::window().and_then(|w| w.document()) {
+			Some(document) => document,
+			None => return,
+		};
+		let _ = (|| -> Result<(), JsValue> {
+			let overlay = document.create_element("div")?;
+			if self.fatal() {
+				overlay.set_attribute("style", "position: fixed; top: 0; left: 0; right: 0; padding: 1em; background: #300; color: #fcc; font-family: sans-serif; z-index: 1;")?;
+			} else {
+				overlay.set_attribute("style", "position: fixed; bottom: 0; left: 0; right: 0; padding: 0 1em; background: rgba(48, 32, 0, 0.8); color: #fec; font-family: sans-serif; z-index: 1; pointer-events: none;")?;
+			}
+			let title = document.create_element("h2")?;
+			title.set_text_content(Some(if self.fatal() { "reactor failed to start" } else { "reactor is running without textures" }));
+			let detail = document.create_element("pre")?;
+			detail.set_attribute("style", "white-space: pre-wrap;")?;
+			detail.set_text_content(Some(&self.to_string()));
+			overlay.append_child(&title)?;
+			overlay.append_child(&detail)?;
+			match document.body() {
+				Some(body) => body.append_child(&overlay)?,
+				None => document.document_element().ok_or("no document element")?.append_child(&overlay)?,
+			};
+			Ok(())
+		})();
+	}
+}
@@ -5,7 +5,7 @@ varying highp float blend;
 uniform sampler2D tex;
 
 void main() {
-	highp vec4 c = (1.0 - blend) * col + blend * texture2D(tex,uv);
+	highp vec4 c = (1.0 - blend) * col + blend * texture2D(tex,uv) * col; //col tints textures, it is white for untinted ones
 	gl_FragColor = vec4(c.rgb * c.a,c.a);
 	//gl_FragColor = (1.0 - blend) * col + blend * texture2D(tex,uv);
 }
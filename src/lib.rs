@@ -1,6 +1,6 @@
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
-use web_sys::{WebGlProgram,WebGlRenderingContext,HtmlImageElement,WebGlShader,MouseEvent,HtmlCanvasElement};
+use web_sys::{WebGlRenderingContext,Event,MouseEvent,KeyboardEvent,HtmlCanvasElement,HtmlElement};
 use std::rc::Rc;
 use std::cell::{RefCell,Cell};
 use self::WebGlRenderingContext as GL;
@@ -13,119 +13,82 @@ macro_rules! log {
 
 mod reactor;
 use reactor::Reactor;
+use reactor::config::{Config,Physics};
 mod vertex;
-use vertex::Vertex;
+mod renderer;
+use renderer::{Renderer,WebGlRenderer,Camera};
 mod prelude;
 use prelude::*;
+mod error;
+use error::StartupError;
 
 #[wasm_bindgen(start)]
 pub fn start() -> Result<(), JsValue> {
 	#[cfg(feature = "console_error_panic_hook")]
 	console_error_panic_hook::set_once();
 	
-	let document = window().document().unwrap();
-	let canvas = document.get_element_by_id("canvas").unwrap();
-	let canvas = Rc::new(canvas.dyn_into::<web_sys::HtmlCanvasElement>()?);
-	let context = Rc::new(canvas.get_context("webgl")?.expect("browser does not support webgl").dyn_into::<GL>()?);
-	
-	let vert_shader = compile_shader(
-		&context,
-		GL::VERTEX_SHADER,
-		include_str!("vs.vs"),
-	)?;
-	let frag_shader = compile_shader(
-		&context,
-		GL::FRAGMENT_SHADER,
-		include_str!("fs.fs"),
-	)?;
-	let program = link_program(&context, &vert_shader, &frag_shader)?;
-	context.use_program(Some(&program));
-	
-	let buffer = context.create_buffer().ok_or("failed to create buffer")?;
-	context.bind_buffer(GL::ARRAY_BUFFER, Some(&buffer));
-	
-	let stride = std::mem::size_of::<Vertex>();
-	
-	context.vertex_attrib_pointer_with_i32(0, 3, GL::FLOAT, false, stride as i32, 0);
-	context.vertex_attrib_pointer_with_i32(1, 4, GL::FLOAT, false, stride as i32, 12);
-	context.vertex_attrib_pointer_with_i32(2, 2, GL::FLOAT, false, stride as i32, 12+16);
-	context.vertex_attrib_pointer_with_i32(3, 1, GL::FLOAT, false, stride as i32, 12+16+8);
-	context.enable_vertex_attrib_array(0); context.enable_vertex_attrib_array(1); context.enable_vertex_attrib_array(2); context.enable_vertex_attrib_array(3);
-	
-	let image = Rc::new(RefCell::new(HtmlImageElement::new().unwrap()));
-	let image2 = image.clone();
-	
-	let context2 = context.clone();
-	
-	let onload = Closure::wrap(Box::new(move|| {
-		let texture = context2.create_texture().expect("failed to create texture");
-		context2.active_texture(GL::TEXTURE0);
-		context2.bind_texture(GL::TEXTURE_2D, Some(&texture));
-		context2.pixel_storei(GL::UNPACK_FLIP_Y_WEBGL, 1);
-		context2.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MIN_FILTER, GL::NEAREST as i32);
-		context2.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MAG_FILTER, GL::NEAREST as i32);
-		context2.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_S, GL::CLAMP_TO_EDGE as i32);
-		context2.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_T, GL::CLAMP_TO_EDGE as i32);
-		//context2.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MIN_LOD, 0);
-		//context2.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MAX_LOD, 0);
-		//context2.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MAX_LEVEL, 0);
-		
-		context2.tex_image_2d_with_u32_and_u32_and_image(
-			GL::TEXTURE_2D,
-			0,
-			GL::RGBA as i32,
-			GL::RGBA,
-			GL::UNSIGNED_BYTE,
-			&image2.borrow(),
-		).expect("");
+	run().map_err(|e| {
+		e.show();
+		e.to_string().into()
+	})
+}
+
+fn run() -> Result<(), StartupError> {
+	let document = web_sys::window().ok_or(StartupError::NoWindow)?.document().ok_or(StartupError::NoDocument)?;
+	let canvas = document.get_element_by_id("canvas").ok_or(StartupError::NoCanvas)?;
+	let canvas = Rc::new(canvas.dyn_into::<web_sys::HtmlCanvasElement>().map_err(|_| StartupError::NoCanvas)?);
+	let context = Rc::new(canvas.get_context("webgl")?.ok_or(StartupError::NoWebGl)?.dyn_into::<GL>().map_err(|_| StartupError::NoWebGl)?);
+	
+	let renderer = Rc::new(RefCell::new(WebGlRenderer::new(context)?));
+	renderer.borrow().load_atlas("textures.png")?;
+	
+	let renderer2 = renderer.clone();
+	let oncontextlost = Closure::wrap(Box::new(move|e: Event| {
+		e.prevent_default(); //otherwise the browser never tries to restore the context
+		renderer2.borrow_mut().context_lost();
+	}) as Box<dyn Fn(Event)>);
+	canvas.add_event_listener_with_callback("webglcontextlost",oncontextlost.as_ref().unchecked_ref())?;
+	oncontextlost.forget();
+	
+	let renderer2 = renderer.clone();
+	let oncontextrestored = Closure::wrap(Box::new(move|| {
+		if let Err(e) = renderer2.borrow_mut().context_restored() {
+			log!("failed to restore webgl context: {:?}", e);
+		}
 	}) as Box<dyn Fn()>);
+	canvas.add_event_listener_with_callback("webglcontextrestored",oncontextrestored.as_ref().unchecked_ref())?;
+	oncontextrestored.forget();
 	
-	image.borrow().set_onload(Some(onload.as_ref().unchecked_ref()));
-	image.borrow().set_src("textures.png");
-	
-	onload.forget();
+	let camera = Rc::new(Cell::new(Camera::new()));
 	
+	let body = Rc::new(document.body().ok_or(StartupError::NoBody)?);
+	let pixel_ratio = Rc::new(Cell::new(resize_canvas(&canvas, &body, &mut *renderer.borrow_mut(), &camera)?));
 	
-	let offset = Rc::new(Cell::new(vec2(0.0, 0.0)));
-	let offset_location = context.get_uniform_location(&program, "offset");
-	context.uniform2f(offset_location.as_ref(), offset.get().x, offset.get().y);
-	let aspect_ratio_location = context.get_uniform_location(&program, "aspect_ratio");
-	
-	let context2 = context.clone();
+	let renderer2 = renderer.clone();
+	let camera2 = camera.clone();
 	let canvas2 = canvas.clone();
-	let body = document.body().expect("website had no body");
-	let aspect_ratio_location2 = aspect_ratio_location.clone();
+	let body2 = body.clone();
+	let pixel_ratio2 = pixel_ratio.clone();
 	let onresize = Closure::wrap(Box::new(move|| {
-		let (w, h) = (body.client_width(), body.client_height());
-		context2.uniform1f(aspect_ratio_location2.as_ref(), h as f32 / w as f32);
-		context2.viewport(0, 0, w, h);
-		canvas2.set_attribute("width",&w.to_string()).expect("failed to set canvas width");
-		canvas2.set_attribute("height",&h.to_string()).expect("failed to set canvas height");
+		match resize_canvas(&canvas2, &body2, &mut *renderer2.borrow_mut(), &camera2) {
+			Ok(ratio) => pixel_ratio2.set(ratio),
+			Err(e) => log!("failed to resize canvas: {}", e),
+		}
 	}) as Box<dyn Fn()>);
-	window().add_event_listener_with_callback("resize",onresize.as_ref().unchecked_ref()).expect("failed to add resize listener");
+	window().add_event_listener_with_callback("resize",onresize.as_ref().unchecked_ref())?;
 	onresize.forget();
 	
-	let body = document.body().expect("no body present on document");
-	let (w, h) = (body.client_width(), body.client_height());
-	canvas.set_attribute("width",&w.to_string()).expect("failed to set canvas width");
-	canvas.set_attribute("height",&h.to_string()).expect("failed to set canvas height");
-	context.uniform1f(aspect_ratio_location.as_ref(), h as f32 / w as f32);
-	context.viewport(0, 0, w, h);
-	
-	context.clear_color(0.0, 0.0, 0.0, 1.0);
-	context.enable(GL::DEPTH_TEST);
-	context.depth_func(GL::GEQUAL);
-	context.enable(GL::BLEND);
-	context.blend_func(GL::SRC_ALPHA, GL::ONE_MINUS_SRC_ALPHA);
-	
-	let reactor = Rc::new(RefCell::new(Reactor::new()));
+	let reactor = Rc::new(RefCell::new(Reactor::with_config(config_from_url())));
+	if let Some(save) = storage().and_then(|s| s.get_item(SAVE_KEY).ok().flatten()) {
+		reactor.borrow_mut().load(&save);
+	}
 	
 	let reactor2 = reactor.clone();
 	let canvas2 = canvas.clone();
-	let offset2 = offset.clone();
+	let camera2 = camera.clone();
 	let onclick = Closure::wrap(Box::new(move|e: MouseEvent| {
 		let m = screen_coords(e.client_x(), e.client_y(), &canvas2);
-		reactor2.borrow_mut().click(m - offset2.get());
+		reactor2.borrow_mut().click(m - camera2.get().offset);
 	}) as Box<dyn Fn(MouseEvent)>);
 	canvas.set_onclick(Some(onclick.as_ref().unchecked_ref()));
 	onclick.forget();
@@ -145,58 +108,66 @@ pub fn start() -> Result<(), JsValue> {
 	onmouseup.forget();
 	
 	let canvas2 = canvas.clone();
-	let offset2 = offset.clone();
+	let camera2 = camera.clone();
 	let reactor2 = reactor.clone();
-	let context2 = context.clone();
 	let onmove = Closure::wrap(Box::new(move|e: MouseEvent| {
 		if let Some(initial_pos) = c.get() {
 			let pos = screen_coords(e.client_x(), e.client_y(), &canvas2);
 			let delta = vec2(e.movement_x(), -e.movement_y()).f32() * 2.0 / canvas2.client_height() as f32;
-			if !reactor2.borrow_mut().drag(initial_pos - offset2.get(), pos - offset2.get(), delta) {
-				offset2.set(modulus(offset2.get() + delta + 5.0, 10.0) - 5.0);
-				context2.uniform2f(offset_location.as_ref(), offset2.get().x, offset2.get().y);
+			let mut cam = camera2.get();
+			if !reactor2.borrow_mut().drag(initial_pos - cam.offset, pos - cam.offset, delta) {
+				cam.offset = modulus(cam.offset + delta + 5.0, 10.0) - 5.0;
+				camera2.set(cam);
 			}
 		}
 	}) as Box<dyn Fn(MouseEvent)>);
 	canvas.set_onmousemove(Some(onmove.as_ref().unchecked_ref()));
 	onmove.forget();
 	
+	let reactor2 = reactor.clone();
+	let renderer2 = renderer.clone();
+	let onkeydown = Closure::wrap(Box::new(move|e: KeyboardEvent| {
+		if e.key() == "t" { //cycle color themes
+			let name = reactor2.borrow_mut().next_theme();
+			log!("theme: {}", name);
+		} else if e.key() == "p" {
+			let enabled = renderer2.borrow_mut().toggle_post_processing();
+			log!("post processing: {}", enabled);
+		}
+	}) as Box<dyn Fn(KeyboardEvent)>);
+	window().add_event_listener_with_callback("keydown",onkeydown.as_ref().unchecked_ref())?;
+	onkeydown.forget();
+	
 	let f = Rc::new(RefCell::new(None));
 	let g = f.clone();
-	
-	// Note that `Float32Array::view` is somewhat dangerous (hence the
-	// `unsafe`!). This is creating a raw view into our module's
-	// `WebAssembly.Memory` buffer, but if we allocate more pages for ourself
-	// (aka do a memory allocation in Rust) it'll cause the buffer to change,
-	// causing the `Float32Array` to be invalid.
-	// As a result, after `Float32Array::view` we have to be very careful not to
-	// do any memory allocations before it's dropped.
+	let mut frames_since_save = 0;
 	
 	*g.borrow_mut() = Some(Closure::wrap(Box::new(move|| {
-		context.clear(GL::COLOR_BUFFER_BIT | GL::DEPTH_BUFFER_BIT);
-		context.clear_depth(-1.1);
+		if window().device_pixel_ratio() != pixel_ratio.get() { //moved to another monitor or zoomed, which doesn't always fire resize
+			match resize_canvas(&canvas, &body, &mut *renderer.borrow_mut(), &camera) {
+				Ok(ratio) => pixel_ratio.set(ratio),
+				Err(e) => {
+					log!("failed to resize canvas: {}", e);
+					pixel_ratio.set(window().device_pixel_ratio()); //don't retry every frame
+				},
+			}
+		}
 		let mut reactor = reactor.borrow_mut();
 		reactor.tick();
-		let mut verts = reactor.vertices();
-		let len = verts.len();
+		let mut renderer = renderer.borrow_mut();
+		renderer.set_effects(reactor.effects());
+		renderer.render(reactor.vertices(), &camera.get());
 		
-		unsafe {
-			let ptr = verts.as_mut_ptr() as *mut f32; //note that transmuting like this is safe because both Vertex and Vec2/Vec3 are repr(C) so they are just a number of floats anyway
-			let len = len * stride / 4;
-			let cap = verts.capacity() * stride;
-			std::mem::forget(verts);
-			let verts = Vec::from_raw_parts(ptr, len, cap);
-			let verts = js_sys::Float32Array::view(&verts);
-			
-			context.buffer_data_with_array_buffer_view(
-				GL::ARRAY_BUFFER,
-				&verts,
-				GL::STATIC_DRAW,
-			);
+		frames_since_save += 1;
+		if frames_since_save >= SAVE_INTERVAL {
+			frames_since_save = 0;
+			if let Some(storage) = storage() {
+				if let Err(e) = storage.set_item(SAVE_KEY, &reactor.save()) {
+					log!("failed to save: {:?}", e);
+				}
+			}
 		}
 		
-		context.draw_arrays(GL::TRIANGLES, 0, len as i32);
-		
 		request_animation_frame(f.borrow().as_ref().unwrap());
 	}) as Box<dyn FnMut()>));
 	
@@ -205,6 +176,14 @@ pub fn start() -> Result<(), JsValue> {
 	Ok(())
 }
 
+const SAVE_KEY: &str = "reactor_save";
+const SAVE_INTERVAL: u32 = 300; //frames, about 5 seconds
+
+//None when the browser blocks storage, the game still runs but won't remember anything
+fn storage() -> Option<web_sys::Storage> {
+	window().local_storage().ok().flatten()
+}
+
 fn request_animation_frame(f: &Closure<dyn FnMut()>) {
 	window()
 		.request_animation_frame(f.as_ref().unchecked_ref())
@@ -216,50 +195,41 @@ fn window() -> web_sys::Window {
 	web_sys::window().expect("no global `window` exists")
 }
 
-fn compile_shader(
-	context: &GL,
-	shader_type: u32,
-	source: &str,
-) -> Result<WebGlShader, String> {
-	let shader = context
-		.create_shader(shader_type)
-		.ok_or_else(|| String::from("Unable to create shader object"))?;
-	context.shader_source(&shader, source);
-	context.compile_shader(&shader);
-	
-	if context.get_shader_parameter(&shader, GL::COMPILE_STATUS).as_bool().unwrap_or(false)	{
-		Ok(shader)
-	} else {
-		Err(context.get_shader_info_log(&shader).unwrap_or_else(|| String::from("Unknown error creating shader")))
+//the page url can pick options, e.g. index.html?physics=kinetics
+fn config_from_url() -> Config {
+	let mut config = Config::default();
+	let search = window().location().search().unwrap_or_default();
+	for pair in search.trim_start_matches('?').split('&') {
+		let mut kv = pair.splitn(2, '=');
+		if let (Some("physics"), Some(name)) = (kv.next(), kv.next()) {
+			match Physics::from_name(name) {
+				Some(physics) => config.physics = physics,
+				None => log!("unknown physics model {}, using {:?}", name, config.physics),
+			}
+		}
 	}
+	config
 }
 
-fn link_program(
-	context: &GL,
-	vert_shader: &WebGlShader,
-	frag_shader: &WebGlShader,
-) -> Result<WebGlProgram, String> {
-	let program = context
-		.create_program()
-		.ok_or_else(|| String::from("Unable to create shader object"))?;
-	
-	context.attach_shader(&program, vert_shader);
-	context.attach_shader(&program, frag_shader);
-	context.link_program(&program);
-	
-	if context
-		.get_program_parameter(&program, GL::LINK_STATUS)
-		.as_bool()
-		.unwrap_or(false)
-	{
-		Ok(program)
-	} else {
-		Err(context
-			.get_program_info_log(&program)
-			.unwrap_or_else(|| String::from("Unknown error creating program object")))
-	}
+//keeps the css size of the canvas at the size of the page, but makes the drawing buffer devicePixelRatio times bigger so it's sharp on hidpi screens
+//returns the pixel ratio it sized for
+fn resize_canvas(canvas: &HtmlCanvasElement, body: &HtmlElement, renderer: &mut dyn Renderer, camera: &Cell<Camera>) -> Result<f64, StartupError> {
+	let pixel_ratio = window().device_pixel_ratio();
+	let (w, h) = (body.client_width(), body.client_height());
+	let (buffer_w, buffer_h) = ((w as f64 * pixel_ratio).round() as i32, (h as f64 * pixel_ratio).round() as i32);
+	let style = canvas.style();
+	style.set_property("width",&format!("{}px", w))?;
+	style.set_property("height",&format!("{}px", h))?;
+	canvas.set_attribute("width",&buffer_w.to_string())?;
+	canvas.set_attribute("height",&buffer_h.to_string())?;
+	let mut c = camera.get();
+	c.aspect_ratio = h as f32 / w as f32;
+	camera.set(c);
+	renderer.resize(buffer_w, buffer_h);
+	Ok(pixel_ratio)
 }
 
+//mouse events and client sizes are both in css pixels, so this doesn't need to know about the pixel ratio
 fn screen_coords(x: i32, y: i32, canvas: &HtmlCanvasElement) -> Vec2<f32> {
 	let x = x as f32;
 	let y = -y as f32;
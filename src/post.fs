@@ -0,0 +1,27 @@
+precision highp float;
+
+varying vec2 uv;
+
+uniform sampler2D scene;
+uniform vec2 texel; //size of one pixel in uv
+uniform float glow;
+uniform float shimmer;
+uniform vec4 shimmer_rect; //min xy, max zw in uv
+uniform float time;
+
+void main() {
+	vec2 p = uv;
+	if (shimmer > 0.0 && all(greaterThan(uv,shimmer_rect.xy)) && all(lessThan(uv,shimmer_rect.zw))) {
+		vec2 local = (uv - shimmer_rect.xy) / (shimmer_rect.zw - shimmer_rect.xy);
+		float fade = sin(local.x * 3.14159) * sin(local.y * 3.14159); //no distortion at the edges of the rect
+		p += shimmer * fade * 3.0 * texel * vec2(sin(uv.y * 120.0 + time * 6.0),cos(uv.x * 90.0 + time * 4.0));
+	}
+	vec4 c = texture2D(scene,p);
+	vec3 bloom = vec3(0.0);
+	for (int x = -3; x <= 3; x++) {
+		for (int y = -3; y <= 3; y++) {
+			bloom += max(texture2D(scene,p + vec2(float(x),float(y)) * texel * 3.0).rgb - 0.6,0.0);
+		}
+	}
+	gl_FragColor = vec4(c.rgb + glow * bloom * (4.0 / 49.0),1.0);
+}
@@ -0,0 +1,8 @@
+attribute vec2 pos;
+
+varying vec2 uv;
+
+void main() {
+	uv = pos * 0.5 + 0.5;
+	gl_Position = vec4(pos,0.0,1.0);
+}
@@ -0,0 +1,98 @@
+use crate::vertex::Vertex;
+use crate::prelude::*;
+
+//easing curves, all map 0..1 to 0..1
+
+pub fn ease_out_cubic(t: f32) -> f32 {
+	let t = 1.0 - t.max(0.0).min(1.0);
+	1.0 - t * t * t
+}
+
+pub fn ease_in_out_sine(t: f32) -> f32 {
+	let t = t.max(0.0).min(1.0);
+	(1.0 - (PI * t).cos()) / 2.0
+}
+
+//displayed value that chases its target, rate is roughly how many times per second it closes the gap
+#[derive(Debug,Copy,Clone)]
+pub struct Smoothed {
+	val: f32,
+	rate: f32,
+}
+
+impl Smoothed {
+	pub fn new(val: f32, rate: f32) -> Self {
+		Self { val, rate, }
+	}
+	
+	pub fn update(&mut self, target: f32, dt: f32) {
+		self.val += (target - self.val) * (1.0 - (-self.rate * dt).exp());
+	}
+	
+	pub fn snap(&mut self, target: f32) {
+		self.val = target;
+	}
+	
+	pub fn get(&self) -> f32 {
+		self.val
+	}
+}
+
+//plays once when something becomes visible, for panels that get unlocked
+//only play() starts it, so things that were already unlocked when the game loaded don't slide in again
+#[derive(Debug,Copy,Clone)]
+pub struct Reveal {
+	t: f32,
+	duration: f32,
+}
+
+impl Reveal {
+	pub fn new(duration: f32) -> Self {
+		Self { t: 1.0, duration, }
+	}
+	
+	pub fn play(&mut self) {
+		self.t = 0.0;
+	}
+	
+	pub fn update(&mut self, dt: f32) {
+		self.t = (self.t + dt / self.duration).min(1.0);
+	}
+	
+	pub fn progress(&self) -> f32 {
+		ease_out_cubic(self.t)
+	}
+	
+	pub fn done(&self) -> bool {
+		self.t >= 1.0
+	}
+}
+
+//free running clock for blinking/pulsing things
+#[derive(Debug,Copy,Clone)]
+pub struct Pulse {
+	time: f32,
+}
+
+impl Pulse {
+	pub fn new() -> Self {
+		Self { time: 0.0, }
+	}
+	
+	pub fn update(&mut self, dt: f32) {
+		self.time = modulus(self.time + dt, 1000.0);
+	}
+	
+	pub fn value(&self, freq: f32) -> f32 { //0..1, starts at 0
+		ease_in_out_sine(modulus(self.time * freq * 2.0, 2.0).min(2.0 - modulus(self.time * freq * 2.0, 2.0)))
+	}
+}
+
+//applies a reveal to vertices that were just emitted, fading them in while sliding them from offset to their real position
+pub fn fade_slide(v: &mut [Vertex], progress: f32, offset: Vec2<f32>) {
+	let shift = offset * (1.0 - progress);
+	for vert in v.iter_mut() {
+		vert.col[3] *= progress;
+		vert.pos = vec3(vert.pos.x + shift.x, vert.pos.y + shift.y, vert.pos.z);
+	}
+}
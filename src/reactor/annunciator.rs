@@ -0,0 +1,144 @@
+use crate::vertex::*;
+use crate::prelude::*;
+use super::collision::rect;
+use super::theme::Theme;
+use super::animation::Pulse;
+use super::events::Event;
+use super::{PANEL_SPRITE,PANEL_BORDER};
+
+const COLUMNS: usize = 3;
+const LAMP_WIDTH: f32 = 0.13;
+const LAMP_HEIGHT: f32 = 0.05;
+const LAMP_GAP: f32 = 0.01;
+const TEXT_HEIGHT: f32 = 0.016; //so the longest label fits on a lamp
+const ALERT_BLINK_FREQ: f32 = 3.0; //came on and nobody looked yet
+const CLEARED_BLINK_FREQ: f32 = 1.0; //went away again before anyone looked
+
+#[derive(Debug,Copy,Clone,PartialEq)]
+pub enum Alarm {
+	HeatHigh,
+	NeutronsHigh,
+	FuelBlocked,
+	WasteFull,
+	TankLow,
+	Damaged,
+}
+
+pub const ALARMS: [Alarm; 6] = [Alarm::HeatHigh, Alarm::NeutronsHigh, Alarm::FuelBlocked, Alarm::WasteFull, Alarm::TankLow, Alarm::Damaged];
+
+impl Alarm {
+	fn label(self) -> &'static str {
+		match self {
+			Alarm::HeatHigh => "HEAT HIGH",
+			Alarm::NeutronsHigh => "FLUX HIGH",
+			Alarm::FuelBlocked => "FUEL BLOCK",
+			Alarm::WasteFull => "WASTE FULL",
+			Alarm::TankLow => "TANK LOW",
+			Alarm::Damaged => "DAMAGE",
+		}
+	}
+	
+	fn index(self) -> usize {
+		ALARMS.iter().position(|&a| a == self).unwrap()
+	}
+}
+
+//remembers which alarm conditions held last tick so only the crossings turn into events
+pub struct Crossings {
+	held: [bool; ALARMS.len()], //indexed like ALARMS
+}
+
+impl Crossings {
+	pub fn new() -> Self {
+		Self { held: [false; ALARMS.len()], }
+	}
+	
+	pub fn update(&mut self, conditions: [bool; ALARMS.len()], events: &mut Vec<Event>) {
+		for (i, &alarm) in ALARMS.iter().enumerate() {
+			if conditions[i] != self.held[i] {
+				events.push(Event::Alarm(alarm, conditions[i]));
+			}
+		}
+		self.held = conditions;
+	}
+}
+
+//a lamp stays lit until its condition is gone and it's been acknowledged, and blinks until acknowledged
+#[derive(Debug,Copy,Clone)]
+struct Lamp {
+	active: bool,
+	acknowledged: bool,
+}
+
+pub struct Annunciator {
+	lamps: [Lamp; ALARMS.len()], //indexed like ALARMS
+}
+
+impl Annunciator {
+	pub fn new() -> Self {
+		Self {
+			lamps: [Lamp { active: false, acknowledged: true }; ALARMS.len()],
+		}
+	}
+	
+	pub fn observe(&mut self, event: Event) {
+		if let Event::Alarm(alarm, active) = event {
+			let lamp = &mut self.lamps[alarm.index()];
+			lamp.active = active;
+			if active {
+				lamp.acknowledged = false;
+			}
+		}
+	}
+	
+	pub fn lit(&self, alarm: Alarm) -> bool {
+		let lamp = self.lamps[alarm.index()];
+		lamp.active || !lamp.acknowledged
+	}
+	
+	pub fn acknowledge(&mut self) {
+		for lamp in self.lamps.iter_mut() {
+			lamp.acknowledged = true;
+		}
+	}
+	
+	pub fn render(&self, v: &mut Vec<Vertex>, t: &Theme, pulse: &Pulse) {
+		let (pos, size) = (self.panel_pos(), self.panel_size());
+		nine_slice(v, pos.extend(1.0), size, PANEL_BORDER, PANEL_SPRITE, t.panel_cool);
+		text(v, vec3(pos.x + 0.02, pos.y + size.y - 0.02 - TEXT_HEIGHT, 2.0), TEXT_HEIGHT, "ALARMS", t.bar_background);
+		for (i, &alarm) in ALARMS.iter().enumerate() {
+			let lamp = self.lamps[i];
+			let blink = if lamp.active { ALERT_BLINK_FREQ } else { CLEARED_BLINK_FREQ };
+			let on = self.lit(alarm) && (lamp.acknowledged || pulse.value(blink) > 0.5);
+			let lamp_pos = self.lamp_pos(i);
+			rounded_rect(v, lamp_pos.extend(2.0), vec2(LAMP_WIDTH, LAMP_HEIGHT), 0.01, if on { t.panel_hot } else { t.bar_background });
+			let label = alarm.label();
+			let label_pos = lamp_pos + vec2(LAMP_WIDTH - text_width(TEXT_HEIGHT, label), LAMP_HEIGHT - TEXT_HEIGHT) / 2.0;
+			text(v, label_pos.extend(3.0), TEXT_HEIGHT, label, t.text);
+		}
+	}
+	
+	//anywhere on the panel acknowledges every lamp
+	pub fn click(&mut self, m: Vec2<f32>) -> bool {
+		if rect(m, self.panel_pos(), self.panel_size()) {
+			self.acknowledge();
+			return true;
+		}
+		false
+	}
+	
+	fn lamp_pos(&self, i: usize) -> Vec2<f32> {
+		let (column, row) = (i % COLUMNS, i / COLUMNS);
+		let top = self.panel_pos().y + self.panel_size().y - 0.05;
+		vec2(self.panel_pos().x + 0.02 + column as f32 * (LAMP_WIDTH + LAMP_GAP), top - (row + 1) as f32 * (LAMP_HEIGHT + LAMP_GAP))
+	}
+	
+	fn panel_pos(&self) -> Vec2<f32> {
+		vec2(0.05, 0.4)
+	}
+	
+	fn panel_size(&self) -> Vec2<f32> {
+		let rows = (ALARMS.len() + COLUMNS - 1) / COLUMNS;
+		vec2(0.04 + COLUMNS as f32 * LAMP_WIDTH + (COLUMNS - 1) as f32 * LAMP_GAP, 0.07 + rows as f32 * (LAMP_HEIGHT + LAMP_GAP))
+	}
+}
@@ -1,5 +1,6 @@
 use crate::prelude::*;
-use crate::vertex::{Vertex,Tex,transformed_quad};
+use crate::vertex::{Vertex,Tex,Color,quad,transformed_quad};
+use super::theme::Theme;
 
 pub fn circle(pos: Vec2<f32>, center: Vec2<f32>, radius: f32) -> bool {
 	let pos = pos - center;
@@ -27,12 +28,14 @@ pub struct Dial {
 	pub range: Option<(f32, f32)>,
 	pub tex: Tex,
 	pub z_index: f32,
-	pub background: Option<[Vertex; 6]>,
+	pub background: Option<(Vec3<f32>, f32)>, //square drawn behind the dial in the theme's control color
 }
 
 impl Dial {
-	pub fn render(&self, v: &mut Vec<Vertex>) {
-		self.background.as_ref().map(|b| v.extend_from_slice(b));
+	pub fn render(&self, v: &mut Vec<Vertex>, theme: &Theme) {
+		if let Some((pos, size)) = self.background {
+			quad(v, pos, vec2(size, size), Color(theme.control));
+		}
 		transformed_quad(v, self.pos.extend(self.z_index), vec2(self.size, self.size), self.tex, self.trans());
 	}
 	
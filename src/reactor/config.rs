@@ -0,0 +1,28 @@
+use super::damage::Thresholds;
+
+//tunables that can differ between games, everything else in reactor is a fixed constant
+#[derive(Debug,Copy,Clone,Default)]
+pub struct Config {
+	pub damage: Thresholds,
+	pub physics: Physics,
+}
+
+//how neutrons respond to changes, see kinetics.rs
+#[derive(Debug,Copy,Clone,PartialEq,Default)]
+pub enum Physics {
+	#[default]
+	Instant, //the original model, every neutron shows up the tick its fission happens
+	OneGroup, //delayed neutrons from one lumped precursor group
+	SixGroup, //delayed neutrons from the six standard precursor groups
+}
+
+impl Physics {
+	pub fn from_name(name: &str) -> Option<Self> {
+		match name {
+			"instant" => Some(Physics::Instant),
+			"kinetics1" | "one_group" => Some(Physics::OneGroup),
+			"kinetics" | "six_group" => Some(Physics::SixGroup),
+			_ => None,
+		}
+	}
+}
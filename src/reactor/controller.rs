@@ -0,0 +1,229 @@
+use crate::vertex::*;
+use crate::prelude::*;
+use super::collision::rect;
+use super::theme::Theme;
+use super::save::Save;
+use super::{PANEL_SPRITE,PANEL_BORDER};
+
+const ROWS: usize = 7;
+const ROW_HEIGHT: f32 = 0.055;
+const TEXT_HEIGHT: f32 = 0.022;
+const BUTTON_SIZE: f32 = 0.045;
+const SETPOINT_STEP: f32 = 0.1;
+const GAIN_STEP: f32 = 0.05;
+
+//what the controller reads
+#[derive(Debug,Copy,Clone,PartialEq)]
+pub enum Sensor {
+	Heat,
+	Neutrons,
+	Water,
+}
+
+pub const SENSORS: [Sensor; 3] = [Sensor::Heat, Sensor::Neutrons, Sensor::Water];
+
+//what the controller turns, the dials get the output directly and the fuel valve opens when it's over half
+#[derive(Debug,Copy,Clone,PartialEq)]
+pub enum Actuator {
+	TankValve,
+	NeutronValve,
+	FuelValve,
+}
+
+pub const ACTUATORS: [Actuator; 3] = [Actuator::TankValve, Actuator::NeutronValve, Actuator::FuelValve];
+
+impl Sensor {
+	fn name(self) -> &'static str {
+		match self {
+			Sensor::Heat => "HEAT",
+			Sensor::Neutrons => "NEUTRONS",
+			Sensor::Water => "WATER",
+		}
+	}
+}
+
+impl Actuator {
+	fn name(self) -> &'static str {
+		match self {
+			Actuator::TankValve => "TANK VALVE",
+			Actuator::NeutronValve => "NEUTRON VALVE",
+			Actuator::FuelValve => "FUEL VALVE",
+		}
+	}
+	
+	//1 when opening it brings the reading down, so gains can stay positive whatever's wired up
+	fn direction(self, sensor: Sensor) -> f32 {
+		match (self, sensor) {
+			(Actuator::TankValve, Sensor::Water) => -1.0,
+			(Actuator::TankValve, _) | (Actuator::NeutronValve, _) => 1.0,
+			(Actuator::FuelValve, _) => -1.0,
+		}
+	}
+}
+
+pub struct Controller {
+	enabled: bool,
+	sensor: usize, //into SENSORS
+	actuator: usize, //into ACTUATORS
+	setpoint: f32,
+	gains: [f32; 3], //p, i, d
+	integral: f32,
+	last_error: Option<f32>,
+}
+
+impl Controller {
+	pub fn new() -> Self {
+		Self {
+			enabled: false,
+			sensor: 0,
+			actuator: 0,
+			setpoint: 2.0,
+			gains: [0.5, 0.1, 0.0],
+			integral: 0.0,
+			last_error: None,
+		}
+	}
+	
+	pub fn sensor(&self) -> Sensor {
+		SENSORS[self.sensor]
+	}
+	
+	pub fn actuator(&self) -> Actuator {
+		ACTUATORS[self.actuator]
+	}
+	
+	//returns where to set the actuator, 0..1, or None when switched off
+	pub fn tick(&mut self, measured: f32, dt: f32) -> Option<f32> {
+		if !self.enabled {
+			return None;
+		}
+		let error = self.actuator().direction(self.sensor()) * (measured - self.setpoint);
+		let derivative = self.last_error.map(|e| (error - e) / dt).unwrap_or(0.0);
+		self.last_error = Some(error);
+		let [p, i, d] = self.gains;
+		let output = p * error + i * (self.integral + error * dt) + d * derivative;
+		if (output < 1.0 || error < 0.0) && (output > 0.0 || error > 0.0) { //stops integrating while saturated so it doesn't wind up
+			self.integral += error * dt;
+		}
+		Some(output.max(0.0).min(1.0))
+	}
+	
+	pub fn toggle(&mut self) {
+		self.enabled = !self.enabled;
+		self.reset();
+	}
+	
+	pub fn next_sensor(&mut self) {
+		self.sensor = (self.sensor + 1) % SENSORS.len();
+		self.reset();
+	}
+	
+	pub fn next_actuator(&mut self) {
+		self.actuator = (self.actuator + 1) % ACTUATORS.len();
+		self.reset();
+	}
+	
+	//forgets the history, so rewiring doesn't kick the new actuator with the old error
+	fn reset(&mut self) {
+		self.integral = 0.0;
+		self.last_error = None;
+	}
+	
+	pub fn save(&self, save: &mut Save) {
+		save.set("controller.enabled", self.enabled);
+		save.set("controller.sensor", self.sensor);
+		save.set("controller.actuator", self.actuator);
+		save.set("controller.setpoint", self.setpoint);
+		save.set("controller.p", self.gains[0]);
+		save.set("controller.i", self.gains[1]);
+		save.set("controller.d", self.gains[2]);
+	}
+	
+	pub fn load(&mut self, save: &Save) {
+		let default = Self::new();
+		self.enabled = save.get("controller.enabled").unwrap_or(default.enabled);
+		self.sensor = save.get("controller.sensor").filter(|&i| i < SENSORS.len()).unwrap_or(default.sensor);
+		self.actuator = save.get("controller.actuator").filter(|&i| i < ACTUATORS.len()).unwrap_or(default.actuator);
+		self.setpoint = save.get("controller.setpoint").unwrap_or(default.setpoint);
+		self.gains = [
+			save.get("controller.p").unwrap_or(default.gains[0]),
+			save.get("controller.i").unwrap_or(default.gains[1]),
+			save.get("controller.d").unwrap_or(default.gains[2]),
+		];
+		self.reset();
+	}
+	
+	pub fn render(&self, v: &mut Vec<Vertex>, t: &Theme) {
+		let (pos, size) = (self.panel_pos(), self.panel_size());
+		nine_slice(v, pos.extend(1.0), size, PANEL_BORDER, PANEL_SPRITE, t.panel_cool);
+		text(v, vec3(pos.x + 0.02, pos.y + size.y - 0.02 - TEXT_HEIGHT, 2.0), TEXT_HEIGHT, "CONTROLLER", t.bar_background);
+		for i in 0..ROWS {
+			let (row_pos, row_size) = self.row(i);
+			let lit = i != 0 || self.enabled;
+			quad(v, row_pos.extend(2.0), row_size, Color(if lit { t.control } else { t.bar_background }));
+			let label = match i {
+				0 => String::from(if self.enabled { "AUTO ON" } else { "AUTO OFF" }),
+				1 => format!("IN {}", self.sensor().name()),
+				2 => format!("OUT {}", self.actuator().name()),
+				3 => format!("SET {:.1}", self.setpoint),
+				_ => format!("{} {:.2}", ["P", "I", "D"][i - 4], self.gains[i - 4]),
+			};
+			text(v, vec3(row_pos.x + 0.01, row_pos.y + (row_size.y - TEXT_HEIGHT) / 2.0, 3.0), TEXT_HEIGHT, &label, t.text);
+			if i >= 3 {
+				for (button, sign) in [(self.minus(i), "-"), (self.plus(i), "+")].iter() {
+					quad(v, button.extend(3.0), vec2(BUTTON_SIZE, BUTTON_SIZE), Color(t.bar_background));
+					text(v, (*button + (BUTTON_SIZE - TEXT_HEIGHT) / 2.0).extend(4.0), TEXT_HEIGHT, sign, t.text);
+				}
+			}
+		}
+	}
+	
+	//the first three rows cycle or toggle when clicked anywhere, the rest have - and + buttons
+	pub fn click(&mut self, m: Vec2<f32>) -> bool {
+		for i in 0..ROWS {
+			let (row_pos, row_size) = self.row(i);
+			if !rect(m, row_pos, row_size) {
+				continue;
+			}
+			let step = if rect(m, self.minus(i), vec2(BUTTON_SIZE, BUTTON_SIZE)) {
+				-1.0
+			} else if rect(m, self.plus(i), vec2(BUTTON_SIZE, BUTTON_SIZE)) {
+				1.0
+			} else {
+				0.0
+			};
+			match i {
+				0 => self.toggle(),
+				1 => self.next_sensor(),
+				2 => self.next_actuator(),
+				3 => self.setpoint = (self.setpoint + step * SETPOINT_STEP).max(0.0),
+				_ => self.gains[i - 4] = (self.gains[i - 4] + step * GAIN_STEP).max(0.0),
+			}
+			return true;
+		}
+		false
+	}
+	
+	fn row(&self, i: usize) -> (Vec2<f32>, Vec2<f32>) {
+		let (pos, size) = (self.panel_pos(), self.panel_size());
+		let top = pos.y + size.y - 0.06;
+		(vec2(pos.x + 0.02, top - (i + 1) as f32 * ROW_HEIGHT), vec2(size.x - 0.04, ROW_HEIGHT - 0.005))
+	}
+	
+	fn plus(&self, i: usize) -> Vec2<f32> {
+		let (row_pos, row_size) = self.row(i);
+		row_pos + vec2(row_size.x - BUTTON_SIZE, 0.0)
+	}
+	
+	fn minus(&self, i: usize) -> Vec2<f32> {
+		self.plus(i) - vec2(BUTTON_SIZE + 0.01, 0.0)
+	}
+	
+	fn panel_pos(&self) -> Vec2<f32> {
+		vec2(0.55, -0.75)
+	}
+	
+	fn panel_size(&self) -> Vec2<f32> {
+		vec2(0.5, 0.06 + ROW_HEIGHT * ROWS as f32 + 0.02)
+	}
+}
@@ -0,0 +1,80 @@
+//structural integrity of the reactor, heat past the thresholds wears it down until it melts
+
+pub const DAMAGED: f32 = 0.6; //below this the water tank starts leaking
+pub const CRITICAL: f32 = 0.3; //below this the vent jams
+
+#[derive(Debug,Copy,Clone)]
+pub struct Thresholds {
+	pub alarm: f32, //heat that sets off the alarm, nothing gets hurt yet
+	pub damage: f32, //heat above which integrity starts dropping
+	pub meltdown: f32, //heat above which it drops a lot faster
+	pub damage_rate: f32, //integrity lost per second per unit of heat over damage
+	pub meltdown_rate: f32, //extra integrity lost per second per unit of heat over meltdown
+	pub repair_rate: f32, //integrity regained per second while under alarm
+}
+
+impl Default for Thresholds {
+	fn default() -> Self {
+		Self {
+			alarm: 4.0,
+			damage: 5.0,
+			meltdown: 7.0,
+			damage_rate: 0.02,
+			meltdown_rate: 0.2,
+			repair_rate: 0.005,
+		}
+	}
+}
+
+#[derive(Debug,Copy,Clone,PartialEq,PartialOrd)]
+pub enum Stage { //ordered by how bad it is
+	Normal,
+	Alarm,
+	Damaged,
+	Critical,
+	Meltdown,
+}
+
+#[derive(Debug,Copy,Clone)]
+pub struct Integrity {
+	val: f32, //1 is intact, 0 is melted down
+	alarm: bool,
+}
+
+impl Integrity {
+	pub fn new() -> Self {
+		Self { val: 1.0, alarm: false, }
+	}
+	
+	pub fn tick(&mut self, heat: f32, t: &Thresholds, dt: f32) {
+		if self.val <= 0.0 {
+			return; //no coming back from a meltdown without a restart
+		}
+		self.alarm = heat >= t.alarm;
+		if heat > t.damage {
+			self.val -= dt * t.damage_rate * (heat - t.damage);
+			self.val -= dt * t.meltdown_rate * (heat - t.meltdown).max(0.0);
+		} else if !self.alarm {
+			self.val += dt * t.repair_rate;
+		}
+		self.val = self.val.max(0.0).min(1.0);
+	}
+	
+	pub fn get(&self) -> f32 {
+		self.val
+	}
+	
+	pub fn stage(&self) -> Stage {
+		if self.val <= 0.0 {
+			Stage::Meltdown
+		} else if self.val < CRITICAL {
+			Stage::Critical
+		} else if self.val < DAMAGED {
+			Stage::Damaged
+		} else if self.alarm {
+			Stage::Alarm
+		} else {
+			Stage::Normal
+		}
+	}
+}
@@ -0,0 +1,177 @@
+use crate::vertex::*;
+use crate::prelude::*;
+use super::collision::rect;
+use super::theme::Theme;
+use super::save::Save;
+use super::{PANEL_SPRITE,PANEL_BORDER,REACTOR_MASS,WATER_REFILL_RATE,REFINERY_FUEL_CAP,REFINERY_NEUTRON_CAP,REFINERY_WASTE_CAP};
+
+const CREDITS_PER_FUEL: f32 = 10.0; //for every unit of fuel fissioned
+const CREDITS_PER_POWER: f32 = 0.01; //for every unit of turbine power over a second
+const MAX_LEVEL: u32 = 5;
+const ROW_HEIGHT: f32 = 0.07;
+const TEXT_HEIGHT: f32 = 0.022;
+
+#[derive(Debug,Copy,Clone,PartialEq)]
+pub enum Upgrade {
+	ReactorMass,
+	FuelCap,
+	WaterRefill,
+	RefineryCapacity,
+}
+
+pub const UPGRADES: [Upgrade; 4] = [Upgrade::ReactorMass, Upgrade::FuelCap, Upgrade::WaterRefill, Upgrade::RefineryCapacity];
+
+impl Upgrade {
+	fn name(self) -> &'static str {
+		match self {
+			Upgrade::ReactorMass => "CORE MASS",
+			Upgrade::FuelCap => "FUEL CAP",
+			Upgrade::WaterRefill => "REFILL",
+			Upgrade::RefineryCapacity => "REFINERY",
+		}
+	}
+	
+	fn key(self) -> &'static str { //in the save
+		match self {
+			Upgrade::ReactorMass => "upgrade.reactor_mass",
+			Upgrade::FuelCap => "upgrade.fuel_cap",
+			Upgrade::WaterRefill => "upgrade.water_refill",
+			Upgrade::RefineryCapacity => "upgrade.refinery_capacity",
+		}
+	}
+	
+	fn base_cost(self) -> f32 {
+		match self {
+			Upgrade::ReactorMass => 10.0,
+			Upgrade::FuelCap => 15.0,
+			Upgrade::WaterRefill => 10.0,
+			Upgrade::RefineryCapacity => 20.0,
+		}
+	}
+	
+	fn index(self) -> usize {
+		UPGRADES.iter().position(|&u| u == self).unwrap()
+	}
+}
+
+//everything upgrades can change, the simulation reads these instead of the constants they start from
+#[derive(Debug,Copy,Clone)]
+pub struct Modifiers {
+	pub reactor_mass: f32,
+	pub fuel_cap: f32, //before the water tank is unlocked
+	pub tank_fuel_cap: f32, //after
+	pub water_refill_rate: f32,
+	pub refinery_fuel_cap: f32,
+	pub refinery_neutron_cap: f32,
+	pub refinery_waste_cap: f32,
+}
+
+pub struct Economy {
+	credits: f32,
+	levels: [u32; UPGRADES.len()], //indexed like UPGRADES
+}
+
+impl Economy {
+	pub fn new() -> Self {
+		Self { credits: 0.0, levels: [0; UPGRADES.len()], }
+	}
+	
+	pub fn earn(&mut self, fuel_burnt: f32, power: f32, dt: f32) {
+		self.credits += fuel_burnt * CREDITS_PER_FUEL + dt * power * CREDITS_PER_POWER;
+	}
+	
+	pub fn level(&self, upgrade: Upgrade) -> u32 {
+		self.levels[upgrade.index()]
+	}
+	
+	//None when it's maxed out
+	pub fn cost(&self, upgrade: Upgrade) -> Option<f32> {
+		let level = self.level(upgrade);
+		if level < MAX_LEVEL { Some(upgrade.base_cost() * 2f32.powi(level as i32)) } else { None }
+	}
+	
+	pub fn buy(&mut self, upgrade: Upgrade) -> bool {
+		match self.cost(upgrade) {
+			Some(cost) if cost <= self.credits => {
+				self.credits -= cost;
+				self.levels[upgrade.index()] += 1;
+				true
+			},
+			_ => false,
+		}
+	}
+	
+	pub fn modifiers(&self) -> Modifiers {
+		let level = |u| self.level(u) as f32;
+		let refinery = 1.0 + 0.5 * level(Upgrade::RefineryCapacity);
+		Modifiers {
+			reactor_mass: REACTOR_MASS * (1.0 + 0.25 * level(Upgrade::ReactorMass)),
+			fuel_cap: 1.1 + 0.2 * level(Upgrade::FuelCap),
+			tank_fuel_cap: 3.0 + 0.5 * level(Upgrade::FuelCap),
+			water_refill_rate: WATER_REFILL_RATE * (1.0 + 0.5 * level(Upgrade::WaterRefill)),
+			refinery_fuel_cap: REFINERY_FUEL_CAP * refinery,
+			refinery_neutron_cap: REFINERY_NEUTRON_CAP * refinery,
+			refinery_waste_cap: REFINERY_WASTE_CAP * refinery,
+		}
+	}
+	
+	pub fn save(&self, save: &mut Save) {
+		save.set("credits", self.credits);
+		for &upgrade in UPGRADES.iter() {
+			save.set(upgrade.key(), self.level(upgrade));
+		}
+	}
+	
+	pub fn load(&mut self, save: &Save) {
+		self.credits = save.get("credits").filter(|c: &f32| c.is_finite()).unwrap_or(0.0); //a NaN would make every price unaffordable forever
+		for &upgrade in UPGRADES.iter() {
+			self.levels[upgrade.index()] = save.get(upgrade.key()).unwrap_or(0).min(MAX_LEVEL);
+		}
+	}
+	
+	pub fn render(&self, v: &mut Vec<Vertex>, t: &Theme) {
+		let (pos, size) = (self.panel_pos(), self.panel_size());
+		nine_slice(v, pos.extend(1.0), size, PANEL_BORDER, PANEL_SPRITE, t.panel_cool);
+		let top = pos.y + size.y - 0.02 - TEXT_HEIGHT;
+		text(v, vec3(pos.x + 0.02, top, 2.0), TEXT_HEIGHT, "SHOP", t.bar_background);
+		let credits = format!("{:.0}", self.credits.floor());
+		text(v, vec3(pos.x + size.x - 0.02 - text_width(TEXT_HEIGHT, &credits), top, 2.0), TEXT_HEIGHT, &credits, t.power);
+		for (i, &upgrade) in UPGRADES.iter().enumerate() {
+			let (row_pos, row_size) = self.row(i);
+			let cost = self.cost(upgrade);
+			let affordable = cost.map(|c| c <= self.credits).unwrap_or(false);
+			quad(v, row_pos.extend(2.0), row_size, Color(if affordable { t.control } else { t.bar_background }));
+			let y = row_pos.y + (row_size.y - TEXT_HEIGHT) / 2.0;
+			let label = format!("{} {}/{}", upgrade.name(), self.level(upgrade), MAX_LEVEL);
+			text(v, vec3(row_pos.x + 0.01, y, 3.0), TEXT_HEIGHT, &label, t.text);
+			let price = cost.map(|c| format!("{:.0}", c)).unwrap_or_else(|| String::from("MAX"));
+			text(v, vec3(row_pos.x + row_size.x - 0.01 - text_width(TEXT_HEIGHT, &price), y, 3.0), TEXT_HEIGHT, &price, t.text);
+		}
+	}
+	
+	//returns whether the click was on the shop
+	pub fn click(&mut self, m: Vec2<f32>) -> bool {
+		for (i, &upgrade) in UPGRADES.iter().enumerate() {
+			let (row_pos, row_size) = self.row(i);
+			if rect(m, row_pos, row_size) {
+				self.buy(upgrade);
+				return true;
+			}
+		}
+		false
+	}
+	
+	fn row(&self, i: usize) -> (Vec2<f32>, Vec2<f32>) {
+		let (pos, size) = (self.panel_pos(), self.panel_size());
+		let top = pos.y + size.y - 0.06;
+		(vec2(pos.x + 0.02, top - (i + 1) as f32 * ROW_HEIGHT), vec2(size.x - 0.04, ROW_HEIGHT - 0.01))
+	}
+	
+	fn panel_pos(&self) -> Vec2<f32> {
+		vec2(0.55, -0.25)
+	}
+	
+	fn panel_size(&self) -> Vec2<f32> {
+		vec2(0.5, 0.06 + ROW_HEIGHT * UPGRADES.len() as f32 + 0.02)
+	}
+}
@@ -0,0 +1,11 @@
+use super::progression::Unlock;
+use super::annunciator::Alarm;
+
+//things that happened in the simulation, collected by Reactor and handed to whatever wants to react to them
+#[derive(Debug,Copy,Clone,PartialEq)]
+pub enum Event {
+	Ignited,
+	Unlocked(Unlock),
+	Refined(f32), //waste the refinery turned into fuel this tick
+	Alarm(Alarm, bool), //a condition started (true) or stopped (false) holding
+}
@@ -0,0 +1,60 @@
+//what fission leaves behind in the core: iodine decaying into neutron eating xenon, and fuel that's already been partly burnt
+//rates are sped up a lot from the real hours long ones so it plays out within a game
+
+const IODINE_YIELD: f32 = 1.0; //iodine per unit of reaction speed
+const XENON_YIELD: f32 = 0.1; //xenon made directly by fission
+const IODINE_DECAY: f32 = 0.05; //per second, into xenon
+const XENON_DECAY: f32 = 0.03; //per second, into something harmless
+const XENON_BURN: f32 = 0.1; //per second per unit of neutrons, xenon absorbing a neutron stops being a poison
+const XENON_WORTH: f32 = 0.3;
+const BURNUP_RATE: f32 = 0.5; //how fast burning fuel depletes what's left
+const BURNUP_WORTH: f32 = 0.5; //reactivity lost by completely spent fuel
+
+pub struct FissionProducts {
+	iodine: f32,
+	xenon: f32,
+	burnup: f32, //0 is fresh fuel, 1 is spent
+}
+
+impl FissionProducts {
+	pub fn new() -> Self {
+		Self { iodine: 0.0, xenon: 0.0, burnup: 0.0, }
+	}
+	
+	//while running xenon gets burnt as fast as it's made, once neutrons drop the iodine keeps turning into xenon and it piles up
+	pub fn tick(&mut self, reaction_speed: f32, neutrons: f32, dt: f32) {
+		let decayed = dt * IODINE_DECAY * self.iodine;
+		self.iodine += dt * IODINE_YIELD * reaction_speed - decayed;
+		self.xenon += dt * XENON_YIELD * reaction_speed + decayed - dt * (XENON_DECAY + XENON_BURN * neutrons) * self.xenon;
+		self.iodine = self.iodine.max(0.0);
+		self.xenon = self.xenon.max(0.0);
+	}
+	
+	//burnt is how much fuel fissioned, fuel is how much is left in the core
+	pub fn burn(&mut self, burnt: f32, fuel: f32) {
+		if fuel > 0.0 {
+			self.burnup += BURNUP_RATE * burnt / fuel * (1.0 - self.burnup);
+		}
+		self.burnup = self.burnup.max(0.0).min(1.0);
+	}
+	
+	//fresh fuel mixing in lowers the average burnup, fuel is how much was in the core before
+	pub fn refuel(&mut self, added: f32, fuel: f32) {
+		if added > 0.0 {
+			self.burnup *= fuel / (fuel + added);
+		}
+	}
+	
+	//scales how fast fuel fissions
+	pub fn multiplier(&self) -> f32 {
+		(1.0 - BURNUP_WORTH * self.burnup) / (1.0 + XENON_WORTH * self.xenon)
+	}
+	
+	pub fn xenon(&self) -> f32 {
+		self.xenon
+	}
+	
+	pub fn burnup(&self) -> f32 {
+		self.burnup
+	}
+}
@@ -0,0 +1,56 @@
+use super::config::Physics;
+
+//(fraction of all delayed neutrons, decay constant per second) for each precursor group, from Keepin's U-235 thermal fission data
+const SIX_GROUP: [(f32, f32); 6] = [
+	(0.033, 0.0124),
+	(0.219, 0.0305),
+	(0.196, 0.111),
+	(0.395, 0.301),
+	(0.115, 1.14),
+	(0.042, 3.01),
+];
+//the same thing squashed into one group with the same mean delay
+const ONE_GROUP: [(f32, f32); 1] = [(1.0, 0.0767)];
+
+//real uranium only delays 0.65% of its neutrons, which would be invisible this far from critical
+//so the game pretends a much bigger fraction is delayed, the group timings are still the real ones
+const DELAYED_FRACTION: f32 = 0.35;
+const TEMPERATURE_COEFFICIENT: f32 = -0.15; //change in reactivity per unit of heat, negative so hot reactors calm down
+const REFERENCE_HEAT: f32 = 1.0; //heat at which the temperature feedback is neutral
+
+//point kinetics: some of the neutrons from fission come out of decaying fission products instead of straight away
+pub struct Kinetics {
+	groups: &'static [(f32, f32)],
+	precursors: Vec<f32>, //neutrons waiting in each group
+}
+
+impl Kinetics {
+	pub fn new(physics: Physics) -> Option<Self> {
+		let groups: &'static [(f32, f32)] = match physics {
+			Physics::Instant => return None,
+			Physics::OneGroup => &ONE_GROUP,
+			Physics::SixGroup => &SIX_GROUP,
+		};
+		Some(Self {
+			groups,
+			precursors: vec![0.0; groups.len()],
+		})
+	}
+	
+	//takes the neutrons born from fission this tick, returns how many show up this tick
+	pub fn tick(&mut self, born: f32, dt: f32) -> f32 {
+		let mut released = born * (1.0 - DELAYED_FRACTION);
+		for (c, &(fraction, lambda)) in self.precursors.iter_mut().zip(self.groups) {
+			*c += born * DELAYED_FRACTION * fraction;
+			let decayed = *c * (1.0 - (-lambda * dt).exp());
+			*c -= decayed;
+			released += decayed;
+		}
+		released
+	}
+}
+
+//multiplies reactivity, hotter fuel fissions less
+pub fn temperature_feedback(heat: f32) -> f32 {
+	(1.0 + TEMPERATURE_COEFFICIENT * (heat - REFERENCE_HEAT)).max(0.0)
+}
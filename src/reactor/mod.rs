@@ -1,22 +1,47 @@
 use crate::vertex::*;
 use crate::prelude::*;
+use crate::renderer::Effects;
 
 mod collision;
 use collision::*;
+mod particles;
+use particles::Particles;
+mod animation;
+use animation::*;
+pub mod theme;
+use theme::Theme;
+mod damage;
+use damage::{Integrity,Stage};
+pub mod config;
+use config::Config;
+mod kinetics;
+use kinetics::Kinetics;
+mod fission;
+use fission::FissionProducts;
+mod turbine;
+use turbine::Turbine;
+mod save;
+use save::Save;
+mod economy;
+use economy::{Economy,Modifiers};
+mod progression;
+use progression::{Progression,Unlock,Gauges};
+mod events;
+use events::Event;
+mod objectives;
+use objectives::Objectives;
+mod annunciator;
+use annunciator::{Annunciator,Alarm,Crossings,ALARMS};
+mod controller;
+use controller::{Controller,Sensor,Actuator};
+mod rods;
+use rods::ControlRods;
+#[cfg(test)]
+mod tests;
 
-const BLACK: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
-const WHITE: [f32; 4] = [1.0; 4];
-const DARK_GREY: [f32; 4] = [0.5, 0.5, 0.5, 1.0];
-const GREY: [f32; 4] = [0.75, 0.75, 0.75, 1.0];
-const LIGHT_GREY: [f32; 4] = [0.81, 0.81, 0.81, 1.0];
-const RED: [f32; 4] = [1.0, 0.0, 0.0, 1.0];
-const DULL_RED: [f32; 4] = [0.59, 0.25, 0.25, 1.0];
-const DARK_GREEN: [f32; 4] = [0.05, 0.24, 0.06, 1.0];
-const DARK_GREEN_TRANSPARENT: [f32; 4] = [DARK_GREEN[0], DARK_GREEN[1], DARK_GREEN[2], 0.75];
-const YELLOW: [f32; 4] = [0.93, 0.87, 0.47, 1.0];
-const BLUE: [f32; 4] = [0.0, 0.5, 1.0, 1.0];
-const GREEN: [f32; 4] = [0.33, 0.8, 0.2, 1.0];
-const GLASS: [f32; 4] = [0.79, 0.85, 0.86, 1.0];
+
+const PANEL_SPRITE: usize = 7;
+const PANEL_BORDER: f32 = 0.01;
 
 fn blend(a: [f32; 4], b: [f32; 4], ratio: f32) -> [f32; 4] {
 	[
@@ -41,6 +66,44 @@ const EVAPORATION_RATE: f32 = WATER_REFILL_RATE * 0.8;
 const REFINERY_FUEL_FLOW_RATE: f32 = 0.1;
 const REFINERY_NEUTRON_FLOW_RATE: f32 = REFINERY_NEUTRON_DECAY_RATE * REFINERY_NEUTRON_CAP;
 const REFINERY_WASTE_FLOW_RATE: f32 = 1.0;
+const BOILING_POINT: f32 = 3.0; //heat the tank water boils at, see WaterTank::tick
+const DISPLAY_RATE: f32 = 8.0;
+const REVEAL_TIME: f32 = 0.6;
+const WARNING_PULSE_FREQ: f32 = 1.5;
+const TANK_LEAK_RATE: f32 = 0.5; //water lost per second from a damaged tank, scaled by how damaged
+const OVERLAY_Z: f32 = 8.0; //above particles
+const XENON_DISPLAY_MAX: f32 = 2.0; //xenon that fills its bar
+const TANK_LOW: f32 = 5.0; //water left in the tank that sets off its alarm
+
+//what the bars are showing, lags behind the simulation so nothing jumps
+struct Display {
+	fuel: Smoothed,
+	neutrons: Smoothed,
+	water: Smoothed,
+	waste: Smoothed,
+	xenon: Smoothed,
+	burnup: Smoothed,
+	integrity: Smoothed,
+	power: Smoothed,
+	fuel_valve: Reveal,
+	water_tank: Reveal,
+	refinery: Reveal,
+	turbine: Reveal,
+	controller: Reveal,
+	pulse: Pulse,
+}
+
+impl Display {
+	fn reveal(&mut self, unlock: Unlock) -> &mut Reveal {
+		match unlock {
+			Unlock::FuelValve => &mut self.fuel_valve,
+			Unlock::WaterTank => &mut self.water_tank,
+			Unlock::Refinery => &mut self.refinery,
+			Unlock::Turbine => &mut self.turbine,
+			Unlock::Controller => &mut self.controller,
+		}
+	}
+}
 
 pub struct Reactor {
 	fuel: f32,
@@ -48,89 +111,183 @@ pub struct Reactor {
 	heat: f32,
 	water: f32,
 	waste: f32,
-	fuel_valve: [bool; 3],
+	fuel_valve: [bool; 2], //open, blocked
 	vent: bool,
 	water_tank: WaterTank,
 	refinery: Refinery,
+	rods: ControlRods,
+	turbine: Turbine,
+	controller: Controller,
+	economy: Economy,
+	progression: Progression,
+	steam_produced: f32,
+	events: Vec<Event>, //since the last tick, handed out at the end of it
+	objectives: Objectives,
+	crossings: Crossings,
+	annunciator: Annunciator,
+	particles: Particles,
+	display: Display,
+	theme: &'static Theme,
+	config: Config,
+	integrity: Integrity,
+	kinetics: Option<Kinetics>, //None with Physics::Instant
+	period: f32, //seconds for the neutron count to grow by e, negative when shrinking
+	fission: FissionProducts,
 }
 
 impl Reactor {
-	pub fn new() -> Self {
-		Self {
+	pub fn with_config(config: Config) -> Self {
+		let mut r = Self {
 			fuel: 0.9,
 			neutrons: 0.0,
 			heat: 0.0,
 			water: 0.0,
 			waste: 1.0,
-			fuel_valve: [true, false, false],
+			fuel_valve: [true, false],
 			vent: false,
 			water_tank: WaterTank::new(),
 			refinery: Refinery::new(),
-		}
+			rods: ControlRods::new(),
+			turbine: Turbine::new(),
+			controller: Controller::new(),
+			economy: Economy::new(),
+			progression: Progression::new(),
+			steam_produced: 0.0,
+			events: Vec::new(),
+			objectives: Objectives::new(),
+			crossings: Crossings::new(),
+			annunciator: Annunciator::new(),
+			particles: Particles::new(),
+			display: Display {
+				fuel: Smoothed::new(0.0, DISPLAY_RATE),
+				neutrons: Smoothed::new(0.0, DISPLAY_RATE),
+				water: Smoothed::new(0.0, DISPLAY_RATE),
+				waste: Smoothed::new(0.0, DISPLAY_RATE),
+				xenon: Smoothed::new(0.0, DISPLAY_RATE),
+				burnup: Smoothed::new(0.0, DISPLAY_RATE),
+				integrity: Smoothed::new(0.0, DISPLAY_RATE),
+				power: Smoothed::new(0.0, DISPLAY_RATE),
+				fuel_valve: Reveal::new(REVEAL_TIME),
+				water_tank: Reveal::new(REVEAL_TIME),
+				refinery: Reveal::new(REVEAL_TIME),
+				turbine: Reveal::new(REVEAL_TIME),
+				controller: Reveal::new(REVEAL_TIME),
+				pulse: Pulse::new(),
+			},
+			theme: &theme::DEFAULT,
+			config,
+			integrity: Integrity::new(),
+			kinetics: Kinetics::new(config.physics),
+			period: std::f32::INFINITY,
+			fission: FissionProducts::new(),
+		};
+		r.snap_display();
+		r
 	}
 	
 	pub fn tick(&mut self) {
+		if self.melted_down() { //everything stops except the fireworks
+			self.particles.sparks(self.neutron_bar_top(), 0.1);
+			self.particles.tick();
+			self.animate(DT);
+			return;
+		}
+		let m = self.economy.modifiers();
 		if self.heat >= 3.0 || self.neutrons >= 3.0 {
 			self.fuel_valve[1] = true;
 		} else if self.heat < 1.2 {
 			self.fuel_valve[1] = false;
 		}
-		if self.heat >= 3.0 {
-			self.water_tank.unlocked = true;
+		for unlock in self.progression.tick(&self.gauges()) {
+			self.display.reveal(unlock).play();
+			self.events.push(Event::Unlocked(unlock));
 		}
-		if self.waste >= 5.0 {
-			self.refinery.unlocked = true;
+		if self.unlocked(Unlock::Controller) {
+			let measured = self.sense(self.controller.sensor());
+			if let Some(output) = self.controller.tick(measured, DT) {
+				self.actuate(self.controller.actuator(), output);
+			}
 		}
-		let reaction_speed = (REACTION_RATE * self.fuel * self.neutrons.powf(1.02) - 0.001).max(0.0).min(self.fuel / DT);
+		self.rods.tick(self.neutrons);
+		let (reactivity, flux) = if self.kinetics.is_some() { //delayed neutrons and temperature feedback do the stabilizing, so no need for the extra power
+			(self.rods.multiplier() * kinetics::temperature_feedback(self.heat), self.neutrons)
+		} else {
+			(self.rods.multiplier(), self.neutrons.powf(1.02))
+		};
+		let reactivity = reactivity * self.fission.multiplier();
+		let reaction_speed = (REACTION_RATE * reactivity * self.fuel * flux - 0.001).max(0.0).min(self.fuel / DT);
 		let heat_exchange_rate = FUEL_NEUTRON_RATIO * REACTION_RATE * self.neutrons;
+		self.fission.refuel(DT * REACTION_RATE * if self.fuel_valve() { 1.0 } else { 0.0 }, self.fuel);
 		self.fuel += DT * (REACTION_RATE * (if self.fuel_valve() { 1.0 } else { 0.0 } + if self.vent { -5.0 } else { 0.0 }) - reaction_speed);
+		self.fission.burn(DT * reaction_speed, self.fuel);
+		self.fission.tick(reaction_speed, self.neutrons, DT);
 		self.waste += DT * FUEL_WASTE_RATIO * reaction_speed;
-		self.neutrons += DT * (FUEL_NEUTRON_RATIO * reaction_speed - heat_exchange_rate);
-		self.heat += DT * (NEUTRON_HEAT_RATIO * heat_exchange_rate - HEAT_DISSIPATION_RATE * if self.heat > 4.0 { self.heat / 4.0 } else { 1.0 }) / (REACTOR_MASS + self.water);
-		self.fuel = self.fuel.max(0.0).min(if self.water_tank.unlocked { 3.0 } else { 1.1 });
+		self.economy.earn(DT * reaction_speed, self.turbine.power(), DT);
+		let born = DT * FUEL_NEUTRON_RATIO * reaction_speed;
+		let born = match self.kinetics.as_mut() {
+			Some(kinetics) => kinetics.tick(born, DT),
+			None => born,
+		};
+		let neutrons_before = self.neutrons;
+		self.neutrons += born - DT * heat_exchange_rate;
+		let growth = (self.neutrons - neutrons_before) / DT;
+		self.period = if growth.abs() > 1e-6 { self.neutrons / growth } else { std::f32::INFINITY };
+		self.heat += DT * (NEUTRON_HEAT_RATIO * heat_exchange_rate - HEAT_DISSIPATION_RATE * if self.heat > 4.0 { self.heat / 4.0 } else { 1.0 }) / (m.reactor_mass + self.water);
+		self.fuel = self.fuel.max(0.0).min(self.fuel_cap(&m));
 		self.waste = self.waste.max(0.0).min(10.0);
-		if !self.fuel_valve_unlocked() {
-			if self.fuel == 0.0 {
-				self.fuel_valve[2] = true;
-			}
+		if self.vent && self.fuel > 0.0 {
+			self.particles.spray(self.vent_pos());
 		}
 		if self.neutrons >= 3.0 {
+			self.particles.sparks(self.neutron_bar_top(), self.neutrons - 3.0);
 			self.heat += NEUTRON_HEAT_RATIO * (self.neutrons - 3.0);
 			self.neutrons = 3.0;
 		}
-		if self.water_tank.unlocked {
+		if self.unlocked(Unlock::WaterTank) {
+			if self.integrity.get() < damage::DAMAGED {
+				let leak = DT * TANK_LEAK_RATE * (1.0 - self.integrity.get());
+				self.water_tank.water -= leak.min(self.water_tank.water);
+			}
 			let amount = DT * WATER_TRANSFER_RATE * (self.water / 2.5).powf(WATER_PRESSURE); //to water tank
 			self.water -= amount;
 			self.water_tank.add_water(amount, self.heat);
 			let amount = DT * self.water_tank.valve.val * WATER_TRANSFER_RATE * (self.water_tank.water / 25.0).powf(WATER_PRESSURE); //from water tank
-			self.heat = ((REACTOR_MASS + self.water) * self.heat + amount * self.water_tank.heat) / (REACTOR_MASS + self.water + amount);
+			self.heat = ((m.reactor_mass + self.water) * self.heat + amount * self.water_tank.heat) / (m.reactor_mass + self.water + amount);
 			self.water += amount;
 			self.water_tank.water -= amount;
-			self.water_tank.tick();
+			let evaporated = self.water_tank.tick(m.water_refill_rate);
+			self.steam_produced += evaporated;
+			if self.unlocked(Unlock::Turbine) {
+				self.turbine.tick(evaporated);
+			}
+			let (tank_pos, tank_size) = (self.water_tank.panel_pos(), self.water_tank.panel_size());
+			self.particles.steam(tank_pos + vec2(PANEL_BORDER, tank_size.y), tank_size.x - 2.0 * PANEL_BORDER, evaporated);
 		}
-		if self.refinery.unlocked {
+		if self.unlocked(Unlock::Refinery) {
 			let val = (self.refinery.fuel_valve.val - 0.5) * 2.0;
 			let f = if val > 0.0 { //refinery to reactor
-				(REFINERY_FUEL_FLOW_RATE * val.powi(2) * self.fuel * (REFINERY_FUEL_CAP - self.refinery.fuel) / REFINERY_FUEL_CAP)
-					.min(self.fuel.min(REFINERY_FUEL_CAP - self.refinery.fuel) / DT)
+				(REFINERY_FUEL_FLOW_RATE * val.powi(2) * self.fuel * (m.refinery_fuel_cap - self.refinery.fuel) / m.refinery_fuel_cap)
+					.min(self.fuel.min(m.refinery_fuel_cap - self.refinery.fuel) / DT)
 			} else { //reactor to refinery
-				(REFINERY_FUEL_FLOW_RATE * val.abs().powi(2) * -1.0 * self.refinery.fuel * (3.0 - self.fuel) / 3.0)
-					.min(self.refinery.fuel.min(3.0 - self.fuel) / DT)
+				(REFINERY_FUEL_FLOW_RATE * val.abs().powi(2) * -1.0 * self.refinery.fuel * (m.tank_fuel_cap - self.fuel) / m.tank_fuel_cap)
+					.min(self.refinery.fuel.min(m.tank_fuel_cap - self.fuel) / DT)
 			};
+			self.fission.refuel(-DT * f, self.fuel); //only does anything when fuel comes back from the refinery
 			self.fuel -= DT * f;
 			self.refinery.fuel += DT * f;
 			let n = (REFINERY_NEUTRON_FLOW_RATE * self.refinery.neutron_valve.val * self.neutrons)
-				.min(self.neutrons.min(REFINERY_NEUTRON_CAP - self.refinery.neutrons) / DT);
+				.min(self.neutrons.min(m.refinery_neutron_cap - self.refinery.neutrons) / DT);
 			if n < 0.0 {
 				log!("n: {}, neutrons: {}, refinery.neutrons: {}",n,self.neutrons,self.refinery.neutrons);
 			}
 			self.neutrons -= DT * n;
 			self.refinery.neutrons += DT * n;
 			let w = (REFINERY_WASTE_FLOW_RATE * self.refinery.waste_valve.val * self.waste / 10.0)
-				.min(self.waste.min(REFINERY_WASTE_CAP - self.refinery.waste) / DT);
+				.min(self.waste.min(m.refinery_waste_cap - self.refinery.waste) / DT);
 			self.waste -= DT * w;
 			self.refinery.waste += DT * w;
-			self.refinery.tick();
+			let refined = self.refinery.tick(&m);
+			self.events.push(Event::Refined(refined));
 		}
 		{
 			if self.fuel < 0.0 {
@@ -153,75 +310,245 @@ impl Reactor {
 			}
 		}
 		self.heat = self.heat.max(0.0);
+		self.integrity.tick(self.heat, &self.config.damage, DT);
+		if self.melted_down() {
+			self.vent = false;
+		}
+		let conditions = ALARMS.map(|alarm| self.alarm(alarm));
+		self.crossings.update(conditions, &mut self.events);
+		for event in self.events.drain(..) {
+			self.objectives.observe(event);
+			self.annunciator.observe(event);
+		}
+		let gauges = self.gauges();
+		self.objectives.tick(&gauges, DT);
+		self.particles.tick();
+		self.animate(DT);
+	}
+	
+	fn sense(&self, sensor: Sensor) -> f32 {
+		match sensor {
+			Sensor::Heat => self.heat,
+			Sensor::Neutrons => self.neutrons,
+			Sensor::Water => self.water,
+		}
+	}
+	
+	//does nothing if what it's wired to isn't unlocked yet
+	fn actuate(&mut self, actuator: Actuator, output: f32) {
+		match actuator {
+			Actuator::TankValve if self.unlocked(Unlock::WaterTank) => self.water_tank.valve.val = output,
+			Actuator::NeutronValve if self.unlocked(Unlock::Refinery) => self.refinery.neutron_valve.val = output,
+			Actuator::FuelValve if self.fuel_valve_unlocked() => self.fuel_valve[0] = output >= 0.5,
+			_ => {},
+		}
+	}
+	
+	fn alarm(&self, alarm: Alarm) -> bool {
+		match alarm {
+			Alarm::HeatHigh => self.heat >= 3.0,
+			Alarm::NeutronsHigh => self.neutrons >= 3.0,
+			Alarm::FuelBlocked => self.fuel_valve_blocked(),
+			Alarm::WasteFull => self.waste >= 10.0,
+			Alarm::TankLow => self.unlocked(Unlock::WaterTank) && self.water_tank.water < TANK_LOW,
+			Alarm::Damaged => self.integrity.stage() >= Stage::Damaged,
+		}
+	}
+	
+	fn animate(&mut self, dt: f32) {
+		let d = &mut self.display;
+		d.fuel.update(self.fuel, dt);
+		d.neutrons.update(self.neutrons, dt);
+		d.water.update(self.water, dt);
+		d.waste.update(self.waste, dt);
+		d.xenon.update(self.fission.xenon(), dt);
+		d.burnup.update(self.fission.burnup(), dt);
+		d.integrity.update(self.integrity.get(), dt);
+		d.power.update(self.turbine.power(), dt);
+		d.fuel_valve.update(dt);
+		d.water_tank.update(dt);
+		d.refinery.update(dt);
+		d.turbine.update(dt);
+		d.controller.update(dt);
+		d.pulse.update(dt);
+	}
+	
+	//jump the display straight to the simulation state
+	fn snap_display(&mut self) {
+		let d = &mut self.display;
+		d.fuel.snap(self.fuel);
+		d.neutrons.snap(self.neutrons);
+		d.water.snap(self.water);
+		d.waste.snap(self.waste);
+		d.xenon.snap(self.fission.xenon());
+		d.burnup.snap(self.fission.burnup());
+		d.integrity.snap(self.integrity.get());
+		d.power.snap(self.turbine.power());
 	}
 	
 	pub fn vertices(&self) -> Vec<Vertex> {
 		let mut result = Vec::new();
 		let v = &mut result;
 		
+		let t = self.theme;
+		let m = self.economy.modifiers();
+		let fuel_scale = self.fuel_cap(&m).max(3.0); //fuel that fills the bar, grows once upgrades push the cap past it
 		let h = (0.67 * self.heat).min(2.0);
-		let panel_color = if h >= 1.0 { blend(LIGHT_GREY, RED, 0.8 * (h - 1.0)) } else { blend(GREY, LIGHT_GREY, h) };
+		let panel_color = if h >= 1.0 { blend(t.panel_warm, t.panel_hot, 0.8 * (h - 1.0)) } else { blend(t.panel_cool, t.panel_warm, h) };
+		let panel_color = if self.heat >= 3.0 || self.neutrons >= 3.0 || self.integrity.stage() >= Stage::Alarm { blend(panel_color, t.panel_hot, 0.35 * self.display.pulse.value(WARNING_PULSE_FREQ)) } else { panel_color };
+		let (fuel, neutrons, water, waste) = (self.display.fuel.get(), self.display.neutrons.get(), self.display.water.get(), self.display.waste.get());
 		let panel_size = self.panel_size();
 		let panel_pos = -panel_size / 2.0;
 		let bar_size = self.bar_size();
 		let bar_spacing = self.bar_spacing();
 		let bar_gap = self.bar_gap();
-		quad(v, panel_pos.extend(1.0), panel_size, Color(panel_color));
-		quad(v, vec3(panel_pos.x + bar_gap + bar_spacing, -bar_size.y / 2.0, 2.0), bar_size, Color(BLACK));
-		quad(v, vec3(panel_pos.x + bar_gap + bar_spacing * 2.0, -bar_size.y / 2.0, 2.0), bar_size, Color(BLACK));
-		quad(v, vec3(panel_pos.x + bar_gap + bar_spacing * 2.0, -bar_size.y / 2.0, 2.0), bar_size, Color(BLACK));
-		quad(v, vec3(panel_pos.x + bar_gap + bar_spacing * 2.0, bar_size.y / 6.0, 3.0), vec2(bar_size.x, bar_size.y / 3.0), Color(DULL_RED));
-		quad(v, vec3(panel_pos.x + bar_gap + bar_spacing, -bar_size.y / 2.0, 4.0), vec2(bar_size.x, bar_size.y * self.fuel / 3.0), Color(YELLOW));
-		quad(v, vec3(panel_pos.x + bar_gap + bar_spacing * 2.0, -bar_size.y / 2.0, 4.0), vec2(bar_size.x, bar_size.y * self.neutrons.min(2.0) / 3.0), Color(WHITE));
-		quad(v, vec3(panel_pos.x + bar_gap + bar_spacing * 2.0, bar_size.y / 6.0, 4.0), vec2(bar_size.x, bar_size.y * ((self.neutrons - 2.0) / 3.0).max(0.0)), Color(RED));
-		quad(v, vec3(panel_pos.x + bar_gap + bar_spacing, -bar_size.y / 6.0 - 0.0015, 5.0), vec2(bar_size.x + 0.05, 0.003), Color(DARK_GREEN_TRANSPARENT));
-		quad(v, vec3(-0.03, bar_size.y / 2.0 - 0.06, 2.0), vec2(0.06, 0.06), Color(DARK_GREY));
+		nine_slice(v, panel_pos.extend(1.0), panel_size, PANEL_BORDER, PANEL_SPRITE, panel_color);
+		quad(v, vec3(panel_pos.x + bar_gap + bar_spacing, -bar_size.y / 2.0, 2.0), bar_size, Color(t.bar_background));
+		quad(v, vec3(panel_pos.x + bar_gap + bar_spacing * 2.0, -bar_size.y / 2.0, 2.0), bar_size, Color(t.bar_background));
+		quad(v, vec3(panel_pos.x + bar_gap + bar_spacing * 2.0, -bar_size.y / 2.0, 2.0), bar_size, Color(t.bar_background));
+		quad(v, vec3(panel_pos.x + bar_gap + bar_spacing * 2.0, bar_size.y / 6.0, 3.0), vec2(bar_size.x, bar_size.y / 3.0), Color(t.neutron_danger_zone));
+		quad(v, vec3(panel_pos.x + bar_gap + bar_spacing, -bar_size.y / 2.0, 4.0), vec2(bar_size.x, bar_size.y * fuel.min(fuel_scale) / fuel_scale), Color(t.fuel));
+		quad(v, vec3(panel_pos.x + bar_gap + bar_spacing * 2.0, -bar_size.y / 2.0, 4.0), vec2(bar_size.x, bar_size.y * neutrons.min(2.0) / 3.0), Color(t.neutrons));
+		quad(v, vec3(panel_pos.x + bar_gap + bar_spacing * 2.0, bar_size.y / 6.0, 4.0), vec2(bar_size.x, bar_size.y * ((neutrons - 2.0) / 3.0).max(0.0)), Color(t.neutron_overload));
+		quad(v, vec3(panel_pos.x + bar_gap + bar_spacing, -bar_size.y / 2.0 + bar_size.y / fuel_scale - 0.0015, 5.0), vec2(bar_size.x + 0.05, 0.003), Color(t.marker)); //at 1 fuel
+		//xenon and burnup share a slot as two thin bars
+		let thin_bar = vec2(bar_size.x / 2.0 - 0.0015, bar_size.y);
+		let xenon_pos = vec2(panel_pos.x + bar_gap + bar_spacing * 4.0, -bar_size.y / 2.0);
+		let burnup_pos = xenon_pos + vec2(bar_size.x - thin_bar.x, 0.0);
+		quad(v, xenon_pos.extend(2.0), thin_bar, Color(t.bar_background));
+		quad(v, xenon_pos.extend(4.0), vec2(thin_bar.x, bar_size.y * (self.display.xenon.get() / XENON_DISPLAY_MAX).min(1.0)), Color(t.xenon));
+		quad(v, burnup_pos.extend(2.0), thin_bar, Color(t.bar_background));
+		quad(v, burnup_pos.extend(4.0), vec2(thin_bar.x, bar_size.y * self.display.burnup.get()), Color(t.burnup));
+		let integrity = self.display.integrity.get();
+		let integrity_color = if self.integrity.stage() >= Stage::Damaged { blend(t.integrity, t.panel_hot, self.display.pulse.value(WARNING_PULSE_FREQ)) } else { t.integrity };
+		quad(v, self.integrity_bar_pos().extend(2.0), bar_size, Color(t.bar_background));
+		quad(v, self.integrity_bar_pos().extend(4.0), vec2(bar_size.x, bar_size.y * integrity), Color(integrity_color));
+		if self.kinetics.is_some() {
+			let period = if self.period.abs() < 1000.0 { format!("PERIOD {:.0}S", self.period) } else { String::from("PERIOD --") };
+			text(v, vec3(panel_pos.x, panel_pos.y - 0.045, 2.0), 0.03, &period, t.text);
+		}
+		if self.integrity.stage() >= Stage::Alarm {
+			let pulse = self.display.pulse.value(WARNING_PULSE_FREQ);
+			let col = t.panel_hot;
+			text(v, vec3(panel_pos.x + panel_size.x - text_width(0.03, "ALARM"), panel_pos.y - 0.045, 2.0), 0.03, "ALARM", [col[0], col[1], col[2], col[3] * (0.4 + 0.6 * pulse)]);
+		}
+		quad(v, vec3(-0.03, bar_size.y / 2.0 - 0.06, 2.0), vec2(0.06, 0.06), Color(t.control));
 		quad(v, vec3(-0.03 + 0.005, bar_size.y / 2.0 - 0.06 + 0.005, 3.0), vec2(0.05, 0.05), Texture(if self.heat < 2.0 { 0 } else { 4 }));
-		quad(v, vec3(-0.03, bar_size.y / 2.0 - 0.13, 2.0), vec2(0.06, 0.06), Color(DARK_GREY));
+		quad(v, vec3(-0.03, bar_size.y / 2.0 - 0.13, 2.0), vec2(0.06, 0.06), Color(t.control));
 		quad(v, vec3(-0.03 + 0.005, bar_size.y / 2.0 - 0.13 + 0.005, 3.0), vec2(0.05, 0.05), Texture(if self.vent { 2 } else { 1 }));
 		if self.fuel_valve_unlocked() {
+			let start = v.len();
 			let p = self.fuel_valve_pos();
-			quad(v, p.extend(2.0), vec2(0.06, 0.06), Color(DARK_GREY));
+			quad(v, p.extend(2.0), vec2(0.06, 0.06), Color(t.control));
 			let n = if self.fuel_valve_blocked() { 4 } else if self.fuel_valve() { 6 } else { 5 };
 			quad(v, (p + vec2(0.005, 0.005)).extend(2.0), vec2(0.05, 0.05), Texture(n));
+			if !self.display.fuel_valve.done() {
+				fade_slide(&mut v[start..], self.display.fuel_valve.progress(), vec2(0.0, -0.05));
+			}
+		}
+		self.rods.render(v, t);
+		if self.unlocked(Unlock::WaterTank) {
+			let start = v.len();
+			quad(v, vec3(-0.29, -bar_size.y / 2.0, 2.0), bar_size, Color(t.bar_background));
+			quad(v, vec3(-0.29, -bar_size.y / 2.0, 4.0), vec2(bar_size.x, bar_size.y / 2.5 * water), Color(t.water));
+			self.water_tank.render(v, t);
+			if !self.display.water_tank.done() {
+				fade_slide(&mut v[start..], self.display.water_tank.progress(), vec2(-0.1, 0.0));
+			}
+		}
+		if self.unlocked(Unlock::Refinery) {
+			let start = v.len();
+			quad(v, vec3(panel_pos.x + bar_gap + bar_spacing * 3.0, -bar_size.y / 2.0, 2.0), bar_size, Color(t.bar_background));
+			quad(v, vec3(panel_pos.x + bar_gap + bar_spacing * 3.0, -bar_size.y / 2.0, 4.0), vec2(bar_size.x, bar_size.y * waste / 10.0), Color(t.waste));
+			self.refinery.render(v, t, &m);
+			if !self.display.refinery.done() {
+				fade_slide(&mut v[start..], self.display.refinery.progress(), vec2(0.0, -0.1));
+			}
 		}
-		if self.water_tank.unlocked {
-			quad(v, vec3(-0.29, -bar_size.y / 2.0, 2.0), bar_size, Color(BLACK));
-			quad(v, vec3(-0.29, -bar_size.y / 2.0, 4.0), vec2(bar_size.x, bar_size.y / 2.5 * self.water), Color(BLUE));
-			self.water_tank.render(v);
+		if self.unlocked(Unlock::Turbine) {
+			let start = v.len();
+			self.turbine.render(v, t, self.display.power.get());
+			if !self.display.turbine.done() {
+				fade_slide(&mut v[start..], self.display.turbine.progress(), vec2(0.0, 0.1));
+			}
+		}
+		if self.unlocked(Unlock::Controller) {
+			let start = v.len();
+			self.controller.render(v, t);
+			if !self.display.controller.done() {
+				fade_slide(&mut v[start..], self.display.controller.progress(), vec2(0.1, 0.0));
+			}
 		}
-		if self.refinery.unlocked {
-			quad(v, vec3(panel_pos.x + bar_gap + bar_spacing * 3.0, -bar_size.y / 2.0, 2.0), bar_size, Color(BLACK));
-			quad(v, vec3(panel_pos.x + bar_gap + bar_spacing * 3.0, -bar_size.y / 2.0, 4.0), vec2(bar_size.x, bar_size.y * self.waste / 10.0), Color(GREEN));
-			self.refinery.render(v);
+		self.economy.render(v, t);
+		self.objectives.render(v, t);
+		self.annunciator.render(v, t, &self.display.pulse);
+		self.particles.render(v, t);
+		if self.melted_down() {
+			quad(v, vec3(-10.0, -10.0, OVERLAY_Z), vec2(20.0, 20.0), Color(t.overlay));
+			let (height, title) = (0.08, "MELTDOWN");
+			text(v, vec3(-text_width(height, title) / 2.0, 0.05, OVERLAY_Z + 1.0), height, title, t.panel_hot);
+			let (pos, size) = (self.restart_pos(), self.restart_size());
+			nine_slice(v, pos.extend(OVERLAY_Z + 1.0), size, PANEL_BORDER, PANEL_SPRITE, t.control);
+			let label = "RESTART";
+			let height = 0.03;
+			text(v, vec3(pos.x + (size.x - text_width(height, label)) / 2.0, pos.y + (size.y - height) / 2.0, OVERLAY_Z + 2.0), height, label, t.text);
 		}
 		
 		result
 	}
 	
+	pub fn effects(&self) -> Effects {
+		let panel_size = self.panel_size();
+		Effects {
+			glow: if self.melted_down() { 1.0 } else { (self.heat / 4.0).min(1.0) },
+			shimmer: ((self.heat - BOILING_POINT) / 2.0).max(0.0).min(1.0),
+			shimmer_pos: -panel_size / 2.0,
+			shimmer_size: panel_size,
+		}
+	}
+	
 	pub fn click(&mut self, m: Vec2<f32>) {
+		if self.melted_down() { //only the restart button works on the game over screen
+			if rect(m, self.restart_pos(), self.restart_size()) {
+				self.restart();
+			}
+			return;
+		}
+		if self.economy.click(m) || self.annunciator.click(m) {
+			return;
+		}
+		if self.unlocked(Unlock::Controller) && self.controller.click(m) {
+			return;
+		}
 		let panel_size = self.panel_size();
 		let bar_size = self.bar_size();
 		if rect(m, -panel_size / 2.0, panel_size) {
 			if circle(m, vec2(0.0, bar_size.y / 2.0 - 0.06 + 0.005 + 0.05 / 2.0), 0.05 / 2.0) {
 				self.ignite();
-			} else if circle(m, vec2(0.0, bar_size.y / 2.0 - 0.13 + 0.005 + 0.05 / 2.0), 0.05 / 2.0) {
+			} else if circle(m, self.vent_pos(), 0.05 / 2.0) {
 				self.toggle_vent();
 			}
 		} else if rect(m, self.fuel_valve_pos() + vec2(0.005, 0.005), self.fuel_valve_size() - vec2(0.01, 0.01)) {
 			self.toggle_fuel_valve();
 		}
-		if self.water_tank.unlocked { self.water_tank.click(m) };
-		if self.refinery.unlocked { self.refinery.click(m) };
+		self.rods.click(m);
+		if self.unlocked(Unlock::WaterTank) { self.water_tank.click(m) };
+		if self.unlocked(Unlock::Refinery) { self.refinery.click(m) };
 	}
 	
 	pub fn drag(&mut self, initial_pos: Vec2<f32>, pos: Vec2<f32>, delta: Vec2<f32>) -> bool {
-		if self.water_tank.unlocked {
+		if self.melted_down() {
+			return false;
+		}
+		if self.rods.drag(initial_pos, pos, delta) {
+			return true;
+		}
+		if self.unlocked(Unlock::WaterTank) {
 			if self.water_tank.drag(initial_pos, pos, delta) {
 				return true;
 			}
 		}
-		if self.refinery.unlocked {
+		if self.unlocked(Unlock::Refinery) {
 			if self.refinery.drag(initial_pos, pos, delta) {
 				return true;
 			}
@@ -229,10 +556,55 @@ impl Reactor {
 		false
 	}
 	
+	pub fn next_theme(&mut self) -> &'static str {
+		let i = theme::THEMES.iter().position(|t| std::ptr::eq(*t, self.theme)).unwrap_or(0);
+		self.theme = theme::THEMES[(i + 1) % theme::THEMES.len()];
+		self.theme.name
+	}
+	
+	fn melted_down(&self) -> bool {
+		self.integrity.stage() == Stage::Meltdown
+	}
+	
+	//starts a fresh game with the same config, keeping the player's theme and everything that gets saved
+	fn restart(&mut self) {
+		let theme = self.theme;
+		let economy = std::mem::replace(&mut self.economy, Economy::new());
+		let progression = std::mem::replace(&mut self.progression, Progression::new());
+		let objectives = std::mem::replace(&mut self.objectives, Objectives::new());
+		let controller = std::mem::replace(&mut self.controller, Controller::new());
+		*self = Self::with_config(self.config);
+		self.theme = theme;
+		self.economy = economy;
+		self.progression = progression;
+		self.objectives = objectives;
+		self.controller = controller;
+	}
+	
+	//everything that outlives a page reload, see save.rs
+	pub fn save(&self) -> String {
+		let mut save = Save::new();
+		self.economy.save(&mut save);
+		self.progression.save(&mut save);
+		self.objectives.save(&mut save);
+		self.controller.save(&mut save);
+		save.to_string()
+	}
+	
+	pub fn load(&mut self, s: &str) {
+		let save = Save::parse(s);
+		self.economy.load(&save);
+		self.progression.load(&save);
+		self.objectives.load(&save);
+		self.controller.load(&save);
+		self.snap_display();
+	}
+	
 	fn ignite(&mut self) {
 		if self.heat < 2.0 && self.neutrons < 1.0 && self.fuel >= FUEL_NEUTRON_RATIO.recip() * 2.0 {
 			self.neutrons += 1.0;
 			self.fuel -= FUEL_NEUTRON_RATIO.recip() * 2.0;
+			self.events.push(Event::Ignited);
 		}
 	}
 	
@@ -252,6 +624,28 @@ impl Reactor {
 		self.panel_size().y / 30.0
 	}
 	
+	fn integrity_bar_pos(&self) -> Vec2<f32> {
+		let panel_pos = -self.panel_size() / 2.0;
+		vec2(panel_pos.x + self.bar_gap() + self.bar_spacing() * 10.0, -self.bar_size().y / 2.0)
+	}
+	
+	fn restart_pos(&self) -> Vec2<f32> {
+		vec2(-self.restart_size().x / 2.0, -0.1)
+	}
+	
+	fn restart_size(&self) -> Vec2<f32> {
+		vec2(0.24, 0.07)
+	}
+	
+	fn vent_pos(&self) -> Vec2<f32> {
+		vec2(0.0, self.bar_size().y / 2.0 - 0.13 + 0.005 + 0.05 / 2.0)
+	}
+	
+	fn neutron_bar_top(&self) -> Vec2<f32> {
+		let panel_pos = -self.panel_size() / 2.0;
+		vec2(panel_pos.x + self.bar_gap() + self.bar_spacing() * 2.0 + self.bar_size().x / 2.0, self.bar_size().y / 2.0)
+	}
+	
 	fn fuel_valve_pos(&self) -> Vec2<f32> {
 		-self.panel_size()+vec2(-0.15, 0.05)
 	}
@@ -272,17 +666,75 @@ impl Reactor {
 		self.fuel_valve[0] = !self.fuel_valve[0];
 	}
 	
+	fn fuel_cap(&self, m: &Modifiers) -> f32 {
+		if self.unlocked(Unlock::WaterTank) { m.tank_fuel_cap } else { m.fuel_cap }
+	}
+	
 	fn fuel_valve_unlocked(&self) -> bool {
-		self.fuel_valve[2]
+		self.unlocked(Unlock::FuelValve)
+	}
+	
+	fn unlocked(&self, unlock: Unlock) -> bool {
+		self.progression.unlocked(unlock)
+	}
+	
+	fn gauges(&self) -> Gauges {
+		Gauges {
+			fuel: self.fuel,
+			heat: self.heat,
+			neutrons: self.neutrons,
+			waste: self.waste,
+			steam_produced: self.steam_produced,
+		}
 	}
 	
 	fn toggle_vent(&mut self) {
+		if self.integrity.stage() >= Stage::Critical { //jammed
+			return;
+		}
 		self.vent = !self.vent;
 	}
 }
 
+#[cfg(test)]
+impl Reactor {
+	pub fn new() -> Self {
+		Self::with_config(Config::default())
+	}
+	
+	//the state on the tick the water tank gets unlocked
+	pub fn water_tank_unlocked() -> Self {
+		let mut r = Self::new();
+		r.fuel = 1.1;
+		r.neutrons = 1.5;
+		r.heat = 3.0;
+		r.waste = 2.5;
+		r.fuel_valve = [true, true];
+		r.progression.unlock(Unlock::FuelValve);
+		r.progression.unlock(Unlock::WaterTank);
+		r.snap_display();
+		r
+	}
+	
+	//the state on the tick the refinery gets unlocked
+	pub fn refinery_unlocked() -> Self {
+		let mut r = Self::water_tank_unlocked();
+		r.fuel = 2.0;
+		r.neutrons = 1.0;
+		r.heat = 1.5;
+		r.water = 1.2;
+		r.waste = 5.0;
+		r.fuel_valve = [true, false];
+		r.water_tank.water = 18.0;
+		r.water_tank.heat = 0.8;
+		r.water_tank.set_valve(0.3);
+		r.progression.unlock(Unlock::Refinery);
+		r.snap_display();
+		r
+	}
+}
+
 struct WaterTank {
-	unlocked: bool,
 	water: f32,
 	heat: f32,
 	valve: Dial,
@@ -293,7 +745,6 @@ impl WaterTank {
 		let valve_pos = vec2(-0.4, 0.27);
 		let valve_size = 0.1;
 		Self {
-			unlocked: false,
 			water: 20.0,
 			heat: 0.0,
 			valve: Dial {
@@ -303,7 +754,7 @@ impl WaterTank {
 				range: Some((0.0, 0.5)),
 				tex: Texture(3),
 				z_index: 3.0,
-				background: Some(make_quad(valve_pos.extend(2.0), vec2(valve_size,valve_size), Color(DARK_GREY), Mat2::ident())),
+				background: Some((valve_pos.extend(2.0), valve_size)),
 			},
 		}
 	}
@@ -313,28 +764,30 @@ impl WaterTank {
 		self.water += amount;
 	}
 	
-	fn tick(&mut self) {
-		let amount = (DT * WATER_REFILL_RATE).min(25.0 - self.water);
+	fn tick(&mut self, refill_rate: f32) -> f32 { //returns how much water evaporated
+		let amount = (DT * refill_rate).min(25.0 - self.water);
 		self.add_water(amount, 0.0);
-		let evaporation_amount = (EVAPORATION_RATE * DT * self.water.sqrt() * self.heat * (3.1 - self.heat).recip().abs()).min(self.water).max(0.0);
-		let heat_reduction = (3.0 - self.heat).max(0.1); //water boils at heat 3, if tank temp > 3 then water boiling off will be self.heat + 0.1 so heat reduction = 0.1
+		let evaporation_amount = (EVAPORATION_RATE * DT * self.water.sqrt() * self.heat * (BOILING_POINT + 0.1 - self.heat).recip().abs()).min(self.water).max(0.0);
+		let heat_reduction = (BOILING_POINT - self.heat).max(0.1); //if tank temp > BOILING_POINT then water boiling off will be self.heat + 0.1 so heat reduction = 0.1
 		self.water -= evaporation_amount;
 		self.heat -= (evaporation_amount * heat_reduction) / (self.water + 1.0);
 		//self.heat -= DT * (HEAT_DISSIPATION_RATE * 0.1 * self.heat) / (1.0 + self.water); //heat dissipation, disabled because it unbalanced reactor heat dissipation/evaporation requirement
 		self.heat = self.heat.max(0.0);
+		evaporation_amount
 	}
 	
-	fn render(&self, v: &mut Vec<Vertex>) {
+	fn render(&self, v: &mut Vec<Vertex>, t: &Theme) {
 		let heat = (self.heat * 0.67).min(2.0);
-		let panel_color = if heat >= 1.0 { blend(LIGHT_GREY, RED, 0.8 * (heat - 1.0)) } else { blend(GREY, LIGHT_GREY, heat) };
+		let panel_color = if heat >= 1.0 { blend(t.panel_warm, t.panel_hot, 0.8 * (heat - 1.0)) } else { blend(t.panel_cool, t.panel_warm, heat) };
 		let panel_pos = self.panel_pos();
 		let panel_size = self.panel_size();
-		quad(v, panel_pos.extend(1.0), panel_size, Color(panel_color));
-		quad(v, (panel_pos + 0.01).extend(1.5), panel_size - vec2(0.02, 0.01), Color(blend(GLASS, RED, 0.3 * (heat - 1.0).max(0.0))));
-		quad(v, (panel_pos + 0.01).extend(2.0), vec2(panel_size.x - 0.02, (panel_size.y - 0.02) * self.water / 25.0), Color(BLUE));
+		nine_slice(v, panel_pos.extend(1.0), panel_size, PANEL_BORDER, PANEL_SPRITE, panel_color);
+		//tank is open at the top so the glass covers the top border
+		quad(v, (panel_pos + PANEL_BORDER).extend(1.5), panel_size - vec2(2.0 * PANEL_BORDER, PANEL_BORDER), Color(blend(t.glass, t.panel_hot, 0.3 * (heat - 1.0).max(0.0))));
+		quad(v, (panel_pos + PANEL_BORDER).extend(2.0), vec2(panel_size.x - 2.0 * PANEL_BORDER, (panel_size.y - 2.0 * PANEL_BORDER) * self.water / 25.0), Color(t.water));
 		//quad(v, self.valve_pos().extend(2.0), self.valve_size(), Color(DARK_GREY));
 		//transformed_quad(v, (self.valve_pos() + (self.valve_size() / 12.0)).extend(3.0), self.valve_size() * 5.0 / 6.0, Texture(3), Mat2::<f32>::rotate(-self.valve * PI));
-		self.valve.render(v);
+		self.valve.render(v, t);
 	}
 	
 	fn click(&mut self, _m: Vec2<f32>) {
@@ -376,7 +829,6 @@ const REFINERY_NEUTRON_CAP: f32 = 0.2;
 const REFINERY_WASTE_CAP: f32 = 20.0;
 
 struct Refinery {
-	unlocked: bool,
 	fuel: f32,
 	neutrons: f32,
 	waste: f32,
@@ -394,7 +846,6 @@ impl Refinery {
 		let neutron_valve_size = 0.1;
 		let waste_valve_size = 0.1;
 		Self {
-			unlocked: false,
 			fuel: 0.0,
 			neutrons: 0.0,
 			waste: 0.0,
@@ -405,7 +856,7 @@ impl Refinery {
 				range: Some((0.0, 0.5)),
 				tex: Texture(3),
 				z_index: 3.0,
-				background: Some(make_quad(fuel_valve_pos.extend(2.0), vec2(fuel_valve_size,fuel_valve_size), Color(DARK_GREY), Mat2::ident())),
+				background: Some((fuel_valve_pos.extend(2.0), fuel_valve_size)),
 			},
 			neutron_valve: Dial {
 				pos: neutron_valve_pos + neutron_valve_size / 12.0,
@@ -414,7 +865,7 @@ impl Refinery {
 				range: Some((0.0, 0.5)),
 				tex: Texture(3),
 				z_index: 3.0,
-				background: Some(make_quad(neutron_valve_pos.extend(2.0), vec2(neutron_valve_size,neutron_valve_size), Color(DARK_GREY), Mat2::ident())),
+				background: Some((neutron_valve_pos.extend(2.0), neutron_valve_size)),
 			},
 			waste_valve: Dial {
 				pos: waste_valve_pos + waste_valve_size / 12.0,
@@ -423,33 +874,34 @@ impl Refinery {
 				range: Some((0.0, 0.5)),
 				tex: Texture(3),
 				z_index: 3.0,
-				background: Some(make_quad(waste_valve_pos.extend(2.0), vec2(waste_valve_size,waste_valve_size), Color(DARK_GREY), Mat2::ident())),
+				background: Some((waste_valve_pos.extend(2.0), waste_valve_size)),
 			},
 		}
 	}
 	
-	fn tick(&mut self) {
-		let reaction_speed = (REFINERY_REACTION_RATE * self.neutrons * self.waste * self.fuel * (10.0 - self.fuel)).min(self.waste / DT);
+	fn tick(&mut self, m: &Modifiers) -> f32 { //returns how much waste got refined
+		let reaction_speed = (REFINERY_REACTION_RATE * self.neutrons * self.waste * self.fuel * (m.refinery_fuel_cap - self.fuel)).min(self.waste / DT);
 		//log!("{}",self.fuel);
 		self.fuel += DT * WASTE_FUEL_RATIO * reaction_speed;
 		self.waste -= DT * reaction_speed;
 		self.neutrons -= DT * self.neutrons * REFINERY_NEUTRON_DECAY_RATE;
+		DT * reaction_speed
 	}
 	
-	fn render(&self, v: &mut Vec<Vertex>) {
+	fn render(&self, v: &mut Vec<Vertex>, t: &Theme, m: &Modifiers) {
 		let panel_pos = self.panel_pos();
 		let panel_size = self.panel_size();
 		let (bar_size, bar_gap, bar_spacing) = (self.bar_size(), self.bar_gap(), self.bar_spacing());
-		quad(v, panel_pos.extend(1.0), panel_size, Color(DULL_RED));
-		quad(v, (panel_pos + bar_gap).extend(2.0), bar_size, Color(BLACK));
-		quad(v, (panel_pos + bar_gap).extend(3.0), bar_size * vec2(1.0, self.fuel / REFINERY_FUEL_CAP), Color(YELLOW));
-		quad(v, (panel_pos + bar_gap + vec2(bar_spacing, 0.0)).extend(2.0), bar_size, Color(BLACK));
-		quad(v, (panel_pos + bar_gap + vec2(bar_spacing, 0.0)).extend(3.0), bar_size * vec2(1.0, self.neutrons / REFINERY_NEUTRON_CAP), Color(WHITE));
-		quad(v, (panel_pos + bar_gap + vec2(bar_spacing, 0.0) * 4.0).extend(2.0), bar_size, Color(BLACK));
-		quad(v, (panel_pos + bar_gap + vec2(bar_spacing, 0.0) * 4.0).extend(3.0), bar_size * vec2(1.0, self.waste / REFINERY_WASTE_CAP), Color(GREEN));
-		self.fuel_valve.render(v);
-		self.neutron_valve.render(v);
-		self.waste_valve.render(v);
+		nine_slice(v, panel_pos.extend(1.0), panel_size, PANEL_BORDER, PANEL_SPRITE, t.refinery_panel);
+		quad(v, (panel_pos + bar_gap).extend(2.0), bar_size, Color(t.bar_background));
+		quad(v, (panel_pos + bar_gap).extend(3.0), bar_size * vec2(1.0, self.fuel / m.refinery_fuel_cap), Color(t.fuel));
+		quad(v, (panel_pos + bar_gap + vec2(bar_spacing, 0.0)).extend(2.0), bar_size, Color(t.bar_background));
+		quad(v, (panel_pos + bar_gap + vec2(bar_spacing, 0.0)).extend(3.0), bar_size * vec2(1.0, self.neutrons / m.refinery_neutron_cap), Color(t.neutrons));
+		quad(v, (panel_pos + bar_gap + vec2(bar_spacing, 0.0) * 4.0).extend(2.0), bar_size, Color(t.bar_background));
+		quad(v, (panel_pos + bar_gap + vec2(bar_spacing, 0.0) * 4.0).extend(3.0), bar_size * vec2(1.0, self.waste / m.refinery_waste_cap), Color(t.waste));
+		self.fuel_valve.render(v, t);
+		self.neutron_valve.render(v, t);
+		self.waste_valve.render(v, t);
 	}
 	
 	fn click(&mut self, _m: Vec2<f32>) {
@@ -0,0 +1,159 @@
+use crate::vertex::*;
+use crate::prelude::*;
+use super::theme::Theme;
+use super::save::Save;
+use super::events::Event;
+use super::progression::{Gauges,Unlock};
+use super::{PANEL_SPRITE,PANEL_BORDER,OVERLAY_Z};
+
+pub const STEADY_TIME: f32 = 30.0; //seconds the reactor has to hold steady
+const STEADY_TOLERANCE: f32 = 0.02; //heat change per second that still counts as steady
+const STEADY_MIN_NEUTRONS: f32 = 0.5; //a cold dead reactor is steady too, that doesn't count
+const REFINE_GOAL: f32 = 20.0;
+const TOAST_TIME: f32 = 4.0;
+const ROW_HEIGHT: f32 = 0.05;
+const TEXT_HEIGHT: f32 = 0.022;
+
+#[derive(Debug,Copy,Clone,PartialEq)]
+pub enum Objective {
+	FirstIgnition,
+	UnlockWaterTank,
+	SteadyState,
+	RefineWaste,
+}
+
+pub const OBJECTIVES: [Objective; 4] = [Objective::FirstIgnition, Objective::UnlockWaterTank, Objective::SteadyState, Objective::RefineWaste];
+
+impl Objective {
+	fn title(self) -> String { //built from the goals so retuning one can't leave a stale title
+		match self {
+			Objective::FirstIgnition => "IGNITE THE CORE".into(),
+			Objective::UnlockWaterTank => "GET A WATER TANK".into(),
+			Objective::SteadyState => format!("HOLD STEADY {}S", STEADY_TIME),
+			Objective::RefineWaste => format!("REFINE {} WASTE", REFINE_GOAL),
+		}
+	}
+	
+	fn key(self) -> &'static str { //in the save
+		match self {
+			Objective::FirstIgnition => "objective.first_ignition",
+			Objective::UnlockWaterTank => "objective.unlock_water_tank",
+			Objective::SteadyState => "objective.steady_state",
+			Objective::RefineWaste => "objective.refine_waste",
+		}
+	}
+	
+	fn index(self) -> usize {
+		OBJECTIVES.iter().position(|&o| o == self).unwrap()
+	}
+}
+
+pub struct Objectives {
+	done: [bool; OBJECTIVES.len()], //indexed like OBJECTIVES
+	steady_time: f32,
+	last_heat: Option<f32>,
+	refined: f32,
+	toast: Option<(Objective, f32)>, //the last one completed and how long it stays on screen
+}
+
+impl Objectives {
+	pub fn new() -> Self {
+		Self {
+			done: [false; OBJECTIVES.len()],
+			steady_time: 0.0,
+			last_heat: None,
+			refined: 0.0,
+			toast: None,
+		}
+	}
+	
+	pub fn observe(&mut self, event: Event) {
+		match event {
+			Event::Ignited => self.complete(Objective::FirstIgnition),
+			Event::Unlocked(Unlock::WaterTank) => self.complete(Objective::UnlockWaterTank),
+			Event::Refined(amount) => {
+				self.refined += amount;
+				if self.refined >= REFINE_GOAL {
+					self.complete(Objective::RefineWaste);
+				}
+			},
+			_ => {},
+		}
+	}
+	
+	//for objectives about how the reactor behaves over time rather than something that happens
+	pub fn tick(&mut self, gauges: &Gauges, dt: f32) {
+		let rate = self.last_heat.map(|h| (gauges.heat - h).abs() / dt).unwrap_or(std::f32::INFINITY);
+		self.last_heat = Some(gauges.heat);
+		if rate < STEADY_TOLERANCE && gauges.neutrons >= STEADY_MIN_NEUTRONS {
+			self.steady_time += dt;
+			if self.steady_time >= STEADY_TIME - dt / 2.0 { //half a tick of slack for rounding in the summed dts
+				self.complete(Objective::SteadyState);
+			}
+		} else {
+			self.steady_time = 0.0;
+		}
+		if let Some((_, time)) = self.toast.as_mut() {
+			*time -= dt;
+		}
+		if self.toast.map(|(_, time)| time <= 0.0).unwrap_or(false) {
+			self.toast = None;
+		}
+	}
+	
+	pub fn done(&self, objective: Objective) -> bool {
+		self.done[objective.index()]
+	}
+	
+	fn complete(&mut self, objective: Objective) {
+		if !self.done(objective) {
+			self.done[objective.index()] = true;
+			self.toast = Some((objective, TOAST_TIME));
+		}
+	}
+	
+	pub fn save(&self, save: &mut Save) {
+		for &objective in OBJECTIVES.iter() {
+			save.set(objective.key(), self.done(objective));
+		}
+		save.set("objective.refined", self.refined);
+	}
+	
+	pub fn load(&mut self, save: &Save) {
+		for &objective in OBJECTIVES.iter() {
+			self.done[objective.index()] = save.get(objective.key()).unwrap_or(false);
+		}
+		self.refined = save.get("objective.refined").unwrap_or(0.0);
+	}
+	
+	pub fn render(&self, v: &mut Vec<Vertex>, t: &Theme) {
+		let (pos, size) = (self.panel_pos(), self.panel_size());
+		nine_slice(v, pos.extend(1.0), size, PANEL_BORDER, PANEL_SPRITE, t.panel_cool);
+		let top = pos.y + size.y - 0.02 - TEXT_HEIGHT;
+		text(v, vec3(pos.x + 0.02, top, 2.0), TEXT_HEIGHT, "OBJECTIVES", t.bar_background);
+		for (i, &objective) in OBJECTIVES.iter().enumerate() {
+			let y = top - (i + 1) as f32 * ROW_HEIGHT;
+			let check = vec2(pos.x + 0.02 + TEXT_HEIGHT / 2.0, y + TEXT_HEIGHT / 2.0); //center of the checkbox
+			ring(v, check.extend(2.0), TEXT_HEIGHT / 2.0 - 0.002, 0.004, t.bar_background);
+			if self.done(objective) {
+				filled_circle(v, check.extend(3.0), TEXT_HEIGHT / 2.0 - 0.006, t.integrity);
+			}
+			text(v, vec3(pos.x + 0.02 + TEXT_HEIGHT + 0.015, y, 2.0), TEXT_HEIGHT, &objective.title(), t.bar_background);
+		}
+		if let Some((objective, time)) = self.toast {
+			let alpha = (time / 0.5).min(1.0); //fades out over the last half second
+			let message = format!("DONE: {}", objective.title());
+			let height = 0.035;
+			let col = t.power;
+			text(v, vec3(-text_width(height, &message) / 2.0, 0.22, OVERLAY_Z - 1.0), height, &message, [col[0], col[1], col[2], col[3] * alpha]);
+		}
+	}
+	
+	fn panel_pos(&self) -> Vec2<f32> {
+		vec2(0.55, 0.15)
+	}
+	
+	fn panel_size(&self) -> Vec2<f32> {
+		vec2(0.5, 0.06 + ROW_HEIGHT * OBJECTIVES.len() as f32 + 0.01)
+	}
+}
@@ -0,0 +1,112 @@
+use crate::vertex::*;
+use crate::prelude::*;
+use super::DT;
+use super::theme::Theme;
+
+const MAX_PARTICLES: usize = 512;
+const PARTICLE_Z: f32 = 6.0; //above everything else
+const STEAM_PER_WATER: f32 = 60.0;
+
+//colors come from the theme when drawing, so switching themes recolors particles already in flight
+#[derive(Debug,Copy,Clone)]
+enum Kind {
+	Steam,
+	Spark,
+	Spray,
+}
+
+#[derive(Debug,Copy,Clone)]
+struct Particle {
+	pos: Vec2<f32>,
+	vel: Vec2<f32>,
+	accel: Vec2<f32>,
+	age: f32,
+	life: f32,
+	size: f32,
+	grow: f32,
+	kind: Kind,
+}
+
+pub struct Particles {
+	particles: Vec<Particle>,
+	rng: u32,
+	steam: f32, //fractional particles carried over between ticks
+}
+
+impl Particles {
+	pub fn new() -> Self {
+		Self {
+			particles: Vec::new(),
+			rng: 0x2545_f491,
+			steam: 0.0,
+		}
+	}
+	
+	fn random(&mut self) -> f32 { //xorshift, in [0, 1)
+		self.rng ^= self.rng << 13;
+		self.rng ^= self.rng >> 17;
+		self.rng ^= self.rng << 5;
+		(self.rng >> 8) as f32 / (1 << 24) as f32
+	}
+	
+	fn spawn(&mut self, p: Particle) {
+		if self.particles.len() < MAX_PARTICLES {
+			self.particles.push(p);
+		}
+	}
+	
+	//amount is how much water evaporated this tick, particles come off the surface between pos and pos + width
+	pub fn steam(&mut self, pos: Vec2<f32>, width: f32, amount: f32) {
+		self.steam += amount * STEAM_PER_WATER;
+		while self.steam >= 1.0 {
+			self.steam -= 1.0;
+			let x = pos.x + width * self.random();
+			let vel = vec2(0.02 * (self.random() - 0.5), 0.05 + 0.05 * self.random());
+			let life = 1.5 + self.random();
+			self.spawn(Particle { pos: vec2(x, pos.y), vel, accel: vec2(0.0, 0.02), age: 0.0, life, size: 0.015, grow: 0.03, kind: Kind::Steam, });
+		}
+	}
+	
+	//amount is how far over the cap the neutrons went
+	pub fn sparks(&mut self, pos: Vec2<f32>, amount: f32) {
+		let n = (amount * 20.0).ceil().max(1.0).min(8.0) as usize;
+		for _ in 0..n {
+			let theta = PI * (0.1 + 0.8 * self.random());
+			let speed = 0.3 + 0.4 * self.random();
+			let vel = vec2(theta.cos(), theta.sin()) * speed;
+			let life = 0.3 + 0.3 * self.random();
+			self.spawn(Particle { pos, vel, accel: vec2(0.0, -1.5), age: 0.0, life, size: 0.008, grow: 0.0, kind: Kind::Spark, });
+		}
+	}
+	
+	pub fn spray(&mut self, pos: Vec2<f32>) {
+		let theta = -PI * (0.25 + 0.5 * self.random());
+		let speed = 0.2 + 0.2 * self.random();
+		let vel = vec2(theta.cos(), theta.sin()) * speed;
+		let life = 0.6 + 0.4 * self.random();
+		self.spawn(Particle { pos, vel, accel: vec2(0.0, -0.8), age: 0.0, life, size: 0.006, grow: 0.004, kind: Kind::Spray, });
+	}
+	
+	pub fn tick(&mut self) {
+		for p in self.particles.iter_mut() {
+			p.vel = p.vel + p.accel * DT;
+			p.pos = p.pos + p.vel * DT;
+			p.size += p.grow * DT;
+			p.age += DT;
+		}
+		self.particles.retain(|p| p.age < p.life);
+	}
+	
+	pub fn render(&self, v: &mut Vec<Vertex>, t: &Theme) {
+		for p in self.particles.iter() {
+			let fade = 1.0 - p.age / p.life;
+			let col = match p.kind {
+				Kind::Steam => t.steam,
+				Kind::Spark => t.spark,
+				Kind::Spray => t.spray,
+			};
+			let col = [col[0], col[1], col[2], col[3] * fade];
+			quad(v, (p.pos - p.size / 2.0).extend(PARTICLE_Z), vec2(p.size, p.size), Color(col));
+		}
+	}
+}
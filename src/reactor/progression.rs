@@ -0,0 +1,122 @@
+use super::save::Save;
+
+//everything the player can unlock, and the rules for when
+//adding a component means adding a variant and a rule here, tick doesn't need to know
+
+#[derive(Debug,Copy,Clone,PartialEq)]
+pub enum Unlock {
+	FuelValve,
+	WaterTank,
+	Refinery,
+	Turbine,
+	Controller,
+}
+
+//the values conditions can look at, filled in by Reactor each tick
+#[derive(Debug,Copy,Clone,Default)]
+pub struct Gauges {
+	pub fuel: f32,
+	pub heat: f32,
+	pub neutrons: f32,
+	pub waste: f32,
+	pub steam_produced: f32, //water boiled off since the start of the game
+}
+
+#[derive(Debug,Copy,Clone)]
+pub enum Gauge {
+	Fuel,
+	Heat,
+	Waste,
+	SteamProduced,
+}
+
+impl Gauges {
+	pub fn get(&self, gauge: Gauge) -> f32 {
+		match gauge {
+			Gauge::Fuel => self.fuel,
+			Gauge::Heat => self.heat,
+			Gauge::Waste => self.waste,
+			Gauge::SteamProduced => self.steam_produced,
+		}
+	}
+}
+
+#[derive(Debug,Copy,Clone)]
+pub enum Condition {
+	AtLeast(Gauge, f32),
+	AtMost(Gauge, f32),
+}
+
+impl Condition {
+	pub fn holds(self, gauges: &Gauges) -> bool {
+		match self {
+			Condition::AtLeast(gauge, val) => gauges.get(gauge) >= val,
+			Condition::AtMost(gauge, val) => gauges.get(gauge) <= val,
+		}
+	}
+}
+
+pub struct Rule {
+	pub unlock: Unlock,
+	pub key: &'static str, //in the save
+	pub condition: Condition,
+	pub after: &'static [Unlock], //has to be unlocked first
+}
+
+pub const RULES: [Rule; 5] = [
+	Rule { unlock: Unlock::FuelValve, key: "unlock.fuel_valve", condition: Condition::AtMost(Gauge::Fuel, 0.0), after: &[], },
+	Rule { unlock: Unlock::WaterTank, key: "unlock.water_tank", condition: Condition::AtLeast(Gauge::Heat, 3.0), after: &[], },
+	Rule { unlock: Unlock::Refinery, key: "unlock.refinery", condition: Condition::AtLeast(Gauge::Waste, 5.0), after: &[], },
+	Rule { unlock: Unlock::Turbine, key: "unlock.turbine", condition: Condition::AtLeast(Gauge::SteamProduced, 5.0), after: &[Unlock::WaterTank], },
+	Rule { unlock: Unlock::Controller, key: "unlock.controller", condition: Condition::AtLeast(Gauge::SteamProduced, 30.0), after: &[Unlock::WaterTank], },
+];
+
+pub struct Progression {
+	unlocked: [bool; RULES.len()], //indexed like RULES
+}
+
+impl Progression {
+	pub fn new() -> Self {
+		Self { unlocked: [false; RULES.len()], }
+	}
+	
+	//returns what got unlocked this tick, each unlock is only ever returned once
+	//after is checked against what was unlocked before this tick, so chained unlocks come a tick apart
+	pub fn tick(&mut self, gauges: &Gauges) -> Vec<Unlock> {
+		let before = self.unlocked;
+		let mut events = Vec::new();
+		for (i, rule) in RULES.iter().enumerate() {
+			if !self.unlocked[i] && rule.after.iter().all(|&u| before[index(u)]) && rule.condition.holds(gauges) {
+				self.unlocked[i] = true;
+				events.push(rule.unlock);
+			}
+		}
+		events
+	}
+	
+	pub fn unlocked(&self, unlock: Unlock) -> bool {
+		self.unlocked[index(unlock)]
+	}
+	
+	//skips the condition and the event
+	#[cfg(test)]
+	pub fn unlock(&mut self, unlock: Unlock) {
+		self.unlocked[index(unlock)] = true;
+	}
+	
+	pub fn save(&self, save: &mut Save) {
+		for (rule, &unlocked) in RULES.iter().zip(self.unlocked.iter()) {
+			save.set(rule.key, unlocked);
+		}
+	}
+	
+	pub fn load(&mut self, save: &Save) {
+		for (rule, unlocked) in RULES.iter().zip(self.unlocked.iter_mut()) {
+			*unlocked = save.get(rule.key).unwrap_or(false);
+		}
+	}
+}
+
+fn index(unlock: Unlock) -> usize {
+	RULES.iter().position(|r| r.unlock == unlock).unwrap()
+}
@@ -0,0 +1,109 @@
+use crate::vertex::*;
+use crate::prelude::*;
+use super::collision::*;
+use super::theme::Theme;
+use super::{DT,blend};
+
+const ROD_WORTH: f32 = 0.9; //fraction of the reaction fully inserted new rods absorb
+const ROD_SPEED: f32 = 0.3; //insertion per second while following the dial
+const SCRAM_SPEED: f32 = 3.0;
+const ROD_WEAR_RATE: f32 = 0.0005; //wear per second per unit of neutrons at full insertion
+const SCRAM_WEAR: f32 = 0.02; //slamming the rods in is hard on them
+const ROD_COUNT: usize = 3;
+
+//absorbers that slide into the core, the dial sets how far and SCRAM drops them all the way in
+pub struct ControlRods {
+	insertion: Dial, //where the player wants them
+	position: f32, //where they are, 0 is fully withdrawn
+	wear: f32, //0 is new, worn rods absorb less
+	scram: bool,
+}
+
+impl ControlRods {
+	pub fn new() -> Self {
+		let dial_pos = vec2(0.35, 0.0);
+		let dial_size = 0.1;
+		Self {
+			insertion: Dial {
+				pos: dial_pos + dial_size / 12.0,
+				size: dial_size * 5.0 / 6.0,
+				val: 0.0,
+				range: Some((0.0, 0.5)),
+				tex: Texture(3),
+				z_index: 3.0,
+				background: Some((dial_pos.extend(2.0), dial_size)),
+			},
+			position: 0.0,
+			wear: 0.0,
+			scram: false,
+		}
+	}
+	
+	pub fn tick(&mut self, neutrons: f32) {
+		let (target, speed) = if self.scram { (1.0, SCRAM_SPEED) } else { (self.insertion.val, ROD_SPEED) };
+		let step = DT * speed;
+		self.position += (target - self.position).max(-step).min(step);
+		self.wear = (self.wear + DT * ROD_WEAR_RATE * self.position * neutrons).min(1.0);
+	}
+	
+	//scales how fast fuel fissions
+	pub fn multiplier(&self) -> f32 {
+		1.0 - ROD_WORTH * self.position * (1.0 - self.wear)
+	}
+	
+	pub fn toggle_scram(&mut self) {
+		self.scram = !self.scram;
+		if self.scram {
+			self.wear = (self.wear + SCRAM_WEAR).min(1.0);
+		}
+	}
+	
+	pub fn render(&self, v: &mut Vec<Vertex>, t: &Theme) {
+		let (core_pos, core_size) = (self.core_pos(), self.core_size());
+		quad(v, core_pos.extend(2.0), core_size, Color(t.bar_background));
+		let rod_width = core_size.x / (ROD_COUNT * 2 + 1) as f32;
+		let rod_length = core_size.y * self.position;
+		let rod_color = blend(t.rod, t.panel_hot, 0.6 * self.wear);
+		for i in 0..ROD_COUNT {
+			let x = core_pos.x + rod_width * (i * 2 + 1) as f32;
+			quad(v, vec3(x, core_pos.y + core_size.y - rod_length, 3.0), vec2(rod_width, rod_length), Color(rod_color));
+		}
+		self.insertion.render(v, t);
+		let (pos, size) = (self.scram_pos(), self.scram_size());
+		quad(v, pos.extend(2.0), size, Color(if self.scram { t.panel_hot } else { t.control }));
+		let height = 0.025;
+		text(v, vec3(pos.x + (size.x - text_width(height, "SCRAM")) / 2.0, pos.y + (size.y - height) / 2.0, 3.0), height, "SCRAM", t.text);
+		//how much life the rods have left
+		quad(v, vec3(pos.x, pos.y - 0.02, 2.0), vec2(size.x, 0.01), Color(t.bar_background));
+		quad(v, vec3(pos.x, pos.y - 0.02, 3.0), vec2(size.x * (1.0 - self.wear), 0.01), Color(t.integrity));
+	}
+	
+	pub fn click(&mut self, m: Vec2<f32>) -> bool {
+		if rect(m, self.scram_pos(), self.scram_size()) {
+			self.toggle_scram();
+			true
+		} else {
+			false
+		}
+	}
+	
+	pub fn drag(&mut self, initial_pos: Vec2<f32>, pos: Vec2<f32>, delta: Vec2<f32>) -> bool {
+		self.insertion.drag(initial_pos, pos, delta)
+	}
+	
+	fn core_pos(&self) -> Vec2<f32> { //window into the core inside the main panel
+		vec2(0.06, -0.13)
+	}
+	
+	fn core_size(&self) -> Vec2<f32> {
+		vec2(0.13, 0.26)
+	}
+	
+	fn scram_pos(&self) -> Vec2<f32> {
+		vec2(0.35, -0.13)
+	}
+	
+	fn scram_size(&self) -> Vec2<f32> {
+		vec2(0.1, 0.06)
+	}
+}
@@ -0,0 +1,43 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+//persistent state as key=value lines, plain text so old saves keep loading when keys get added or removed
+#[derive(Debug,Default)]
+pub struct Save {
+	values: BTreeMap<String, String>,
+}
+
+impl Save {
+	pub fn new() -> Self {
+		Self::default()
+	}
+	
+	pub fn parse(s: &str) -> Self {
+		let values = s.lines()
+			.filter_map(|line| {
+				let mut kv = line.splitn(2, '=');
+				Some((kv.next()?.trim().to_string(), kv.next()?.trim().to_string()))
+			})
+			.collect();
+		Self { values, }
+	}
+	
+	pub fn set<T: fmt::Display>(&mut self, key: &str, val: T) {
+		self.values.insert(key.to_string(), val.to_string());
+	}
+	
+	//None if it's missing or doesn't parse, callers fall back to a fresh game's value
+	pub fn get<T: FromStr>(&self, key: &str) -> Option<T> {
+		self.values.get(key)?.parse().ok()
+	}
+}
+
+impl fmt::Display for Save {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		for (key, val) in self.values.iter() {
+			writeln!(f, "{}={}", key, val)?;
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,353 @@
+use std::fmt::Write;
+use std::path::Path;
+
+use super::*;
+use super::config::Physics;
+
+//one line per 6 vertices, which is a quad for everything but the vector shapes
+fn dump(verts: &[Vertex]) -> String {
+	let mut s = String::new();
+	writeln!(s, "quads: {}", verts.len() / 6).unwrap();
+	for (i, q) in verts.chunks(6).enumerate() {
+		let (mut min, mut max) = (vec2(std::f32::MAX, std::f32::MAX), vec2(std::f32::MIN, std::f32::MIN));
+		for v in q {
+			min = vec2(min.x.min(v.pos.x), min.y.min(v.pos.y));
+			max = vec2(max.x.max(v.pos.x), max.y.max(v.pos.y));
+		}
+		let v = q[0];
+		write!(s, "{:3}: z {:.1} pos ({:.4}, {:.4}) size ({:.4}, {:.4})", i, v.pos.z, min.x, min.y, max.x - min.x, max.y - min.y).unwrap();
+		if v.blend == 0.0 {
+			writeln!(s, " color [{:.3}, {:.3}, {:.3}, {:.3}]", v.col[0], v.col[1], v.col[2], v.col[3]).unwrap();
+		} else {
+			write!(s, " texture {}", texture_index(v.uv)).unwrap();
+			if v.col != [1.0; 4] {
+				write!(s, " tint [{:.3}, {:.3}, {:.3}, {:.3}]", v.col[0], v.col[1], v.col[2], v.col[3]).unwrap();
+			}
+			writeln!(s).unwrap();
+		}
+	}
+	s
+}
+
+//compares against tests/snapshots/<name>.txt
+//run with UPDATE_SNAPSHOTS=1 to (re)record them instead of comparing
+fn check_snapshot(name: &str, reactor: &Reactor) {
+	let actual = dump(&reactor.vertices());
+	let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("snapshots").join(format!("{}.txt", name));
+	if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+		std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+		std::fs::write(&path, actual).unwrap();
+		eprintln!("recorded snapshot {}", path.display());
+		return;
+	}
+	let expected = std::fs::read_to_string(&path)
+		.unwrap_or_else(|_| panic!("missing snapshot {}, run with UPDATE_SNAPSHOTS=1 to record it", path.display()));
+	assert!(expected == actual, "vertex snapshot {} changed\nexpected:\n{}\nactual:\n{}", name, expected, actual);
+}
+
+#[test]
+fn snapshot_initial() {
+	check_snapshot("initial", &Reactor::new());
+}
+
+#[test]
+fn snapshot_hot_vented() {
+	let mut r = Reactor::new();
+	r.neutrons = 2.5;
+	r.heat = 2.5;
+	r.vent = true;
+	r.fuel_valve = [false, false];
+	r.progression.unlock(Unlock::FuelValve);
+	r.snap_display();
+	check_snapshot("hot_vented", &r);
+}
+
+#[test]
+fn snapshot_water_tank_unlocked() {
+	check_snapshot("water_tank_unlocked", &Reactor::water_tank_unlocked());
+}
+
+#[test]
+fn snapshot_refinery_unlocked() {
+	check_snapshot("refinery_unlocked", &Reactor::refinery_unlocked());
+}
+
+#[test]
+fn water_tank_adds_quads() {
+	let mut r = Reactor::new();
+	let before = r.vertices().len();
+	r.progression.unlock(Unlock::WaterTank);
+	assert!(r.vertices().len() > before);
+}
+
+#[test]
+fn next_theme_cycles_through_every_theme() {
+	let mut r = Reactor::new();
+	let names: Vec<&str> = (0..theme::THEMES.len()).map(|_| r.next_theme()).collect();
+	assert_eq!(names, vec!["high contrast", "deuteranopia", "protanopia", "default"]);
+}
+
+fn melt_down(r: &mut Reactor) {
+	for _ in 0..10_000 {
+		r.heat = 8.0;
+		r.tick();
+		if r.melted_down() {
+			return;
+		}
+	}
+	panic!("reactor never melted down");
+}
+
+#[test]
+fn snapshot_meltdown() {
+	let mut r = Reactor::new();
+	melt_down(&mut r);
+	r.particles = Particles::new();
+	r.snap_display();
+	check_snapshot("meltdown", &r);
+}
+
+#[test]
+fn meltdown_stops_simulation_until_restart() {
+	let mut r = Reactor::new();
+	melt_down(&mut r);
+	let (fuel, heat) = (r.fuel, r.heat);
+	r.tick();
+	assert_eq!((r.fuel, r.heat), (fuel, heat));
+	r.click(r.restart_pos() + r.restart_size() / 2.0);
+	assert!(!r.melted_down());
+	assert_eq!(r.integrity.get(), 1.0);
+}
+
+#[test]
+fn scram_shuts_down_reaction() {
+	let mut r = Reactor::new();
+	r.neutrons = 1.5;
+	r.rods.toggle_scram();
+	for _ in 0..100 {
+		r.tick();
+	}
+	assert!(r.rods.multiplier() < 0.2);
+	let fuel = r.fuel;
+	let mut unscrammed = Reactor::new();
+	unscrammed.neutrons = 1.5;
+	for _ in 0..100 {
+		unscrammed.tick();
+	}
+	assert!(unscrammed.fuel < fuel, "rods should slow fuel burn");
+}
+
+#[test]
+fn delayed_neutrons_lag_then_catch_up() {
+	for physics in [Physics::OneGroup, Physics::SixGroup].iter() {
+		let mut k = Kinetics::new(*physics).unwrap();
+		let first = k.tick(1.0, DT);
+		assert!(first < 0.7, "{:?} released {} straight away", physics, first);
+		let mut last = first;
+		for _ in 0..100_000 {
+			last = k.tick(1.0, DT);
+		}
+		assert!((last - 1.0).abs() < 0.01, "{:?} settled at {}", physics, last);
+	}
+	assert!(Kinetics::new(Physics::Instant).is_none());
+}
+
+#[test]
+fn xenon_builds_up_after_shutdown() {
+	let mut f = FissionProducts::new();
+	for _ in 0..10_000 {
+		f.tick(0.05, 1.0, DT);
+	}
+	let running = f.xenon();
+	for _ in 0..500 {
+		f.tick(0.0, 0.0, DT);
+	}
+	assert!(f.xenon() > running, "xenon went from {} to {} after shutdown", running, f.xenon());
+}
+
+#[test]
+fn burnup_lowers_reactivity_and_fresh_fuel_restores_it() {
+	let mut f = FissionProducts::new();
+	let fresh = f.multiplier();
+	f.burn(0.5, 1.0);
+	let burnt = f.multiplier();
+	assert!(burnt < fresh);
+	f.refuel(1.0, 1.0);
+	assert!(f.multiplier() > burnt);
+}
+
+#[test]
+fn turbine_efficiency_peaks_at_rated_speed() {
+	assert_eq!(turbine::efficiency(0.0), 0.0);
+	assert!(turbine::efficiency(1.0) > turbine::efficiency(0.5));
+	assert!(turbine::efficiency(1.0) > turbine::efficiency(2.0));
+}
+
+#[test]
+fn turbine_turns_steam_into_power() {
+	let mut r = Reactor::refinery_unlocked();
+	r.progression.unlock(Unlock::Turbine);
+	r.water_tank.heat = 2.9;
+	for _ in 0..50 {
+		r.tick();
+	}
+	assert!(r.turbine.power() > 0.0);
+}
+
+#[test]
+fn upgrades_cost_credits_and_change_modifiers() {
+	let mut r = Reactor::new();
+	let before = r.economy.modifiers();
+	assert!(!r.economy.buy(economy::Upgrade::ReactorMass));
+	r.economy.earn(10.0, 0.0, DT);
+	assert!(r.economy.buy(economy::Upgrade::ReactorMass));
+	assert_eq!(r.economy.level(economy::Upgrade::ReactorMass), 1);
+	assert!(r.economy.modifiers().reactor_mass > before.reactor_mass);
+}
+
+#[test]
+fn upgrades_survive_save_and_restart() {
+	let mut r = Reactor::new();
+	r.economy.earn(10.0, 0.0, DT);
+	r.economy.buy(economy::Upgrade::FuelCap);
+	let mut loaded = Reactor::new();
+	loaded.load(&r.save());
+	assert_eq!(loaded.economy.level(economy::Upgrade::FuelCap), 1);
+	melt_down(&mut loaded);
+	loaded.restart();
+	assert_eq!(loaded.economy.level(economy::Upgrade::FuelCap), 1);
+}
+
+#[test]
+fn broken_credits_load_as_zero() {
+	let mut r = Reactor::new();
+	r.load("credits=NaN\n");
+	r.economy.earn(10.0, 0.0, DT);
+	assert!(r.economy.buy(economy::Upgrade::ReactorMass));
+}
+
+#[test]
+fn unlocks_fire_once_in_order() {
+	let mut p = Progression::new();
+	let hot = Gauges { fuel: 1.0, heat: 3.0, steam_produced: 10.0, ..Gauges::default() }; //fuel left so the fuel valve stays locked
+	assert_eq!(p.tick(&hot), vec![Unlock::WaterTank]); //turbine has to wait for the tank
+	assert_eq!(p.tick(&hot), vec![Unlock::Turbine]);
+	assert!(p.tick(&hot).is_empty());
+	assert!(p.unlocked(Unlock::WaterTank) && !p.unlocked(Unlock::Refinery));
+}
+
+#[test]
+fn unlocks_survive_save_without_replaying() {
+	let r = Reactor::refinery_unlocked();
+	let mut loaded = Reactor::new();
+	loaded.load(&r.save());
+	assert!(loaded.unlocked(Unlock::WaterTank) && loaded.unlocked(Unlock::Refinery));
+	assert!(loaded.display.refinery.done());
+	assert!(loaded.progression.tick(&loaded.gauges()).is_empty());
+}
+
+#[test]
+fn objectives_complete_from_events() {
+	let mut r = Reactor::new();
+	r.ignite();
+	r.tick();
+	assert!(r.objectives.done(objectives::Objective::FirstIgnition));
+	assert!(!r.objectives.done(objectives::Objective::RefineWaste));
+	r.objectives.observe(Event::Refined(25.0));
+	assert!(r.objectives.done(objectives::Objective::RefineWaste));
+	let mut loaded = Reactor::new();
+	loaded.load(&r.save());
+	assert!(loaded.objectives.done(objectives::Objective::FirstIgnition));
+}
+
+#[test]
+fn steady_state_needs_time_and_neutrons() {
+	let mut o = Objectives::new();
+	let cold = Gauges { heat: 1.0, ..Gauges::default() };
+	let running = Gauges { heat: 1.0, neutrons: 1.0, ..Gauges::default() };
+	for _ in 0..2000 {
+		o.tick(&cold, DT);
+	}
+	assert!(!o.done(objectives::Objective::SteadyState));
+	let ticks = (objectives::STEADY_TIME / DT).ceil() as usize;
+	for _ in 0..ticks - 1 {
+		o.tick(&running, DT);
+	}
+	assert!(!o.done(objectives::Objective::SteadyState));
+	o.tick(&running, DT);
+	assert!(o.done(objectives::Objective::SteadyState));
+}
+
+#[test]
+fn crossings_fire_once_per_edge() {
+	let mut crossings = Crossings::new();
+	let mut events = Vec::new();
+	let hot = ALARMS.map(|a| a == Alarm::HeatHigh);
+	crossings.update(hot, &mut events);
+	crossings.update(hot, &mut events);
+	crossings.update([false; ALARMS.len()], &mut events);
+	assert_eq!(events, vec![Event::Alarm(Alarm::HeatHigh, true), Event::Alarm(Alarm::HeatHigh, false)]);
+}
+
+#[test]
+fn alarm_lamps_latch_until_acknowledged() {
+	let mut r = Reactor::new();
+	r.heat = 3.5;
+	r.tick();
+	assert!(r.annunciator.lit(Alarm::HeatHigh));
+	r.heat = 0.0;
+	r.tick();
+	assert!(r.annunciator.lit(Alarm::HeatHigh)); //gone but nobody looked
+	r.annunciator.acknowledge();
+	assert!(!r.annunciator.lit(Alarm::HeatHigh));
+	r.heat = 3.5;
+	r.tick();
+	r.annunciator.acknowledge();
+	assert!(r.annunciator.lit(Alarm::HeatHigh)); //acknowledged but still happening
+}
+
+#[test]
+fn controller_opens_tank_valve_when_hot() {
+	let mut r = Reactor::water_tank_unlocked();
+	r.progression.unlock(Unlock::Controller);
+	r.water_tank.valve.val = 0.0;
+	r.tick();
+	assert_eq!(r.water_tank.valve.val, 0.0); //switched off
+	r.controller.toggle();
+	r.heat = 4.0;
+	r.tick();
+	assert!(r.water_tank.valve.val > 0.4);
+	r.heat = 0.5;
+	r.tick();
+	assert!(r.water_tank.valve.val < 0.1);
+}
+
+#[test]
+fn controller_leaves_locked_tank_valve_alone() {
+	let mut r = Reactor::new();
+	r.progression.unlock(Unlock::Controller);
+	r.controller.toggle();
+	r.heat = 2.9; //above the setpoint but not hot enough to unlock the tank
+	r.tick();
+	assert!(!r.unlocked(Unlock::WaterTank));
+	assert_eq!(r.water_tank.valve.val, 0.0);
+}
+
+#[test]
+fn controller_integral_does_not_wind_up() {
+	let mut c = Controller::new();
+	c.toggle();
+	for _ in 0..5000 { //pinned wide open for a long time
+		c.tick(5.0, DT);
+	}
+	assert!(c.tick(1.9, DT).unwrap() < 0.1); //closes again as soon as it's below the setpoint
+}
+
+#[test]
+fn controller_settings_survive_save() {
+	let mut r = Reactor::new();
+	r.controller.next_sensor();
+	let mut loaded = Reactor::new();
+	loaded.load(&r.save());
+	assert_eq!(loaded.controller.sensor(), Sensor::Neutrons);
+}
@@ -0,0 +1,148 @@
+//every color the game draws with, swap the whole thing to restyle
+
+#[derive(Debug,Copy,Clone)]
+pub struct Theme {
+	pub name: &'static str,
+	pub bar_background: [f32; 4],
+	pub panel_cool: [f32; 4],
+	pub panel_warm: [f32; 4],
+	pub panel_hot: [f32; 4], //also used for warnings, so it needs to stand out from panel_warm
+	pub refinery_panel: [f32; 4],
+	pub control: [f32; 4], //backing behind buttons and dials
+	pub fuel: [f32; 4],
+	pub neutrons: [f32; 4],
+	pub neutron_danger_zone: [f32; 4],
+	pub neutron_overload: [f32; 4],
+	pub water: [f32; 4],
+	pub waste: [f32; 4],
+	pub steam: [f32; 4],
+	pub spark: [f32; 4],
+	pub spray: [f32; 4],
+	pub glass: [f32; 4],
+	pub marker: [f32; 4],
+	pub integrity: [f32; 4],
+	pub rod: [f32; 4],
+	pub xenon: [f32; 4],
+	pub burnup: [f32; 4],
+	pub power: [f32; 4],
+	pub text: [f32; 4], //drawn on top of dark overlays and controls
+	pub overlay: [f32; 4], //dims everything behind the meltdown screen
+}
+
+pub static DEFAULT: Theme = Theme {
+	name: "default",
+	bar_background: [0.0, 0.0, 0.0, 1.0],
+	panel_cool: [0.75, 0.75, 0.75, 1.0],
+	panel_warm: [0.81, 0.81, 0.81, 1.0],
+	panel_hot: [1.0, 0.0, 0.0, 1.0],
+	refinery_panel: [0.59, 0.25, 0.25, 1.0],
+	control: [0.5, 0.5, 0.5, 1.0],
+	fuel: [0.93, 0.87, 0.47, 1.0],
+	neutrons: [1.0; 4],
+	neutron_danger_zone: [0.59, 0.25, 0.25, 1.0],
+	neutron_overload: [1.0, 0.0, 0.0, 1.0],
+	water: [0.0, 0.5, 1.0, 1.0],
+	waste: [0.33, 0.8, 0.2, 1.0],
+	steam: [0.92, 0.92, 0.95, 0.45],
+	spark: [1.0, 0.85, 0.4, 1.0],
+	spray: [0.93, 0.87, 0.47, 0.8],
+	glass: [0.79, 0.85, 0.86, 1.0],
+	marker: [0.05, 0.24, 0.06, 0.75],
+	integrity: [0.3, 0.75, 0.7, 1.0],
+	rod: [0.55, 0.55, 0.6, 1.0],
+	xenon: [0.6, 0.4, 0.8, 1.0],
+	burnup: [0.55, 0.45, 0.3, 1.0],
+	power: [1.0, 0.6, 0.1, 1.0],
+	text: [1.0; 4],
+	overlay: [0.0, 0.0, 0.0, 0.7],
+};
+
+pub static HIGH_CONTRAST: Theme = Theme {
+	name: "high contrast",
+	bar_background: [0.0, 0.0, 0.0, 1.0],
+	panel_cool: [0.35, 0.35, 0.35, 1.0],
+	panel_warm: [0.6, 0.6, 0.6, 1.0],
+	panel_hot: [1.0, 0.15, 0.0, 1.0],
+	refinery_panel: [0.35, 0.1, 0.1, 1.0],
+	control: [0.15, 0.15, 0.15, 1.0],
+	fuel: [1.0, 1.0, 0.0, 1.0],
+	neutrons: [1.0; 4],
+	neutron_danger_zone: [0.45, 0.0, 0.0, 1.0],
+	neutron_overload: [1.0, 0.15, 0.0, 1.0],
+	water: [0.0, 0.75, 1.0, 1.0],
+	waste: [0.0, 1.0, 0.2, 1.0],
+	steam: [1.0, 1.0, 1.0, 0.6],
+	spark: [1.0, 1.0, 0.0, 1.0],
+	spray: [1.0, 1.0, 0.0, 0.8],
+	glass: [0.9, 0.95, 1.0, 1.0],
+	marker: [1.0, 0.0, 1.0, 1.0],
+	integrity: [0.0, 1.0, 1.0, 1.0],
+	rod: [0.9, 0.9, 0.9, 1.0],
+	xenon: [0.7, 0.3, 1.0, 1.0],
+	burnup: [1.0, 0.6, 0.0, 1.0],
+	power: [1.0, 0.5, 0.0, 1.0],
+	text: [1.0; 4],
+	overlay: [0.0, 0.0, 0.0, 0.7],
+};
+
+//these two avoid telling things apart by red vs green, warnings go orange and get brighter instead
+//colors are from the Okabe-Ito palette
+
+pub static DEUTERANOPIA: Theme = Theme {
+	name: "deuteranopia",
+	bar_background: [0.0, 0.0, 0.0, 1.0],
+	panel_cool: [0.6, 0.6, 0.65, 1.0],
+	panel_warm: [0.75, 0.75, 0.8, 1.0],
+	panel_hot: [0.84, 0.37, 0.0, 1.0],
+	refinery_panel: [0.8, 0.6, 0.7, 1.0],
+	control: [0.45, 0.45, 0.5, 1.0],
+	fuel: [0.94, 0.89, 0.26, 1.0],
+	neutrons: [1.0; 4],
+	neutron_danger_zone: [0.45, 0.3, 0.15, 1.0],
+	neutron_overload: [0.84, 0.37, 0.0, 1.0],
+	water: [0.34, 0.71, 0.91, 1.0],
+	waste: [0.0, 0.45, 0.7, 1.0],
+	steam: [0.92, 0.92, 0.95, 0.45],
+	spark: [0.94, 0.89, 0.26, 1.0],
+	spray: [0.94, 0.89, 0.26, 0.8],
+	glass: [0.85, 0.85, 0.9, 1.0],
+	marker: [0.0, 0.0, 0.0, 0.75],
+	integrity: [0.0, 0.62, 0.45, 1.0],
+	rod: [0.6, 0.6, 0.7, 1.0],
+	xenon: [0.8, 0.47, 0.65, 1.0],
+	burnup: [0.6, 0.6, 0.6, 1.0],
+	power: [0.9, 0.6, 0.0, 1.0],
+	text: [1.0; 4],
+	overlay: [0.0, 0.0, 0.0, 0.7],
+};
+
+pub static PROTANOPIA: Theme = Theme {
+	name: "protanopia",
+	bar_background: [0.0, 0.0, 0.0, 1.0],
+	panel_cool: [0.6, 0.6, 0.65, 1.0],
+	panel_warm: [0.75, 0.75, 0.8, 1.0],
+	panel_hot: [0.9, 0.62, 0.0, 1.0], //protans see red as dark, so use a bright orange
+	refinery_panel: [0.8, 0.6, 0.7, 1.0],
+	control: [0.45, 0.45, 0.5, 1.0],
+	fuel: [0.94, 0.89, 0.26, 1.0],
+	neutrons: [1.0; 4],
+	neutron_danger_zone: [0.4, 0.35, 0.25, 1.0],
+	neutron_overload: [0.9, 0.62, 0.0, 1.0],
+	water: [0.34, 0.71, 0.91, 1.0],
+	waste: [0.0, 0.45, 0.7, 1.0],
+	steam: [0.92, 0.92, 0.95, 0.45],
+	spark: [0.94, 0.89, 0.26, 1.0],
+	spray: [0.94, 0.89, 0.26, 0.8],
+	glass: [0.85, 0.85, 0.9, 1.0],
+	marker: [0.0, 0.0, 0.0, 0.75],
+	integrity: [0.0, 0.62, 0.45, 1.0],
+	rod: [0.6, 0.6, 0.7, 1.0],
+	xenon: [0.8, 0.47, 0.65, 1.0],
+	burnup: [0.6, 0.6, 0.6, 1.0],
+	power: [0.84, 0.37, 0.0, 1.0],
+	text: [1.0; 4],
+	overlay: [0.0, 0.0, 0.0, 0.7],
+};
+
+//statics rather than consts so each theme has one address, next_theme finds the current one by pointer
+pub static THEMES: [&Theme; 4] = [&DEFAULT, &HIGH_CONTRAST, &DEUTERANOPIA, &PROTANOPIA];
@@ -0,0 +1,74 @@
+use crate::vertex::*;
+use crate::prelude::*;
+use super::theme::Theme;
+use super::{DT,PANEL_SPRITE,PANEL_BORDER};
+
+const STEAM_TORQUE: f32 = 0.5; //rotor acceleration per unit of steam flow
+const FRICTION: f32 = 1.0; //rotor deceleration per unit of speed
+const RATED_SPEED: f32 = 1.0; //speed the blades are designed for
+const PEAK_EFFICIENCY: f32 = 0.9;
+const STEAM_ENERGY: f32 = 10.0; //power from one unit of steam per second at full efficiency
+const SPIN_RATE: f32 = 12.0; //radians per second the drawing turns at rated speed
+const POWER_DISPLAY_MAX: f32 = 100.0; //power that fills the bar
+
+//runs on the steam boiling off the water tank, hotter running means more steam and more power
+pub struct Turbine {
+	speed: f32,
+	angle: f32, //only for drawing
+	power: f32,
+}
+
+//fraction of the steam's energy that becomes power, slow rotors waste it and overspeeding ones lose it to drag
+pub fn efficiency(speed: f32) -> f32 {
+	let x = speed / RATED_SPEED;
+	let curve = if x < 1.0 { x * (2.0 - x) } else { 1.0 - 0.25 * (x - 1.0).powi(2) };
+	PEAK_EFFICIENCY * curve.max(0.0)
+}
+
+impl Turbine {
+	pub fn new() -> Self {
+		Self {
+			speed: 0.0,
+			angle: 0.0,
+			power: 0.0,
+		}
+	}
+	
+	//steam is how much water boiled off this tick
+	pub fn tick(&mut self, steam: f32) {
+		let flow = steam / DT;
+		self.speed += DT * (STEAM_TORQUE * flow - FRICTION * self.speed);
+		self.speed = self.speed.max(0.0);
+		self.angle = modulus(self.angle + DT * SPIN_RATE * self.speed, 2.0 * PI);
+		self.power = flow * STEAM_ENERGY * efficiency(self.speed);
+	}
+	
+	pub fn power(&self) -> f32 {
+		self.power
+	}
+	
+	//power is passed in so the readout can be smoothed by the caller
+	pub fn render(&self, v: &mut Vec<Vertex>, t: &Theme, power: f32) {
+		let (pos, size) = (self.panel_pos(), self.panel_size());
+		nine_slice(v, pos.extend(1.0), size, PANEL_BORDER, PANEL_SPRITE, t.panel_cool);
+		let rotor = 0.16;
+		let rotor_pos = pos + vec2(0.03, (size.y - rotor) / 2.0);
+		quad(v, (rotor_pos - 0.005).extend(2.0), vec2(rotor + 0.01, rotor + 0.01), Color(t.control));
+		transformed_quad(v, rotor_pos.extend(3.0), vec2(rotor, rotor), Texture(3), Mat2::<f32>::rotate(-self.angle));
+		let bar_pos = pos + vec2(size.x - 0.06, 0.03);
+		let bar_size = vec2(0.037, size.y - 0.06);
+		quad(v, bar_pos.extend(2.0), bar_size, Color(t.bar_background));
+		quad(v, bar_pos.extend(3.0), vec2(bar_size.x, bar_size.y * (power / POWER_DISPLAY_MAX).min(1.0)), Color(t.power));
+		let height = 0.025;
+		text(v, vec3(pos.x + 0.03, pos.y + size.y - 0.03 - height, 2.0), height, &format!("POWER {:.1}", power), t.bar_background);
+		text(v, vec3(pos.x + 0.03, pos.y + 0.02, 2.0), height, &format!("EFF {:.0}%", efficiency(self.speed) * 100.0), t.bar_background);
+	}
+	
+	pub fn panel_pos(&self) -> Vec2<f32> {
+		vec2(-0.4, 0.4)
+	}
+	
+	pub fn panel_size(&self) -> Vec2<f32> {
+		vec2(0.35, 0.3)
+	}
+}
@@ -0,0 +1,52 @@
+use crate::prelude::*;
+use crate::vertex::Vertex;
+
+mod webgl;
+pub use webgl::WebGlRenderer;
+mod post;
+mod shader;
+#[cfg(test)]
+mod software;
+
+#[derive(Debug,Copy,Clone)]
+pub struct Camera {
+	pub offset: Vec2<f32>,
+	pub aspect_ratio: f32, //height / width
+}
+
+impl Camera {
+	pub fn new() -> Self {
+		Self {
+			offset: vec2(0.0, 0.0),
+			aspect_ratio: 1.0,
+		}
+	}
+}
+
+//screen effects driven by the game state, backends that can't do them just ignore them
+#[derive(Debug,Copy,Clone)]
+pub struct Effects {
+	pub glow: f32, //0..1
+	pub shimmer: f32, //0..1, heat distortion strength inside the shimmer rect
+	pub shimmer_pos: Vec2<f32>,
+	pub shimmer_size: Vec2<f32>,
+}
+
+impl Effects {
+	pub fn none() -> Self {
+		Self {
+			glow: 0.0,
+			shimmer: 0.0,
+			shimmer_pos: vec2(0.0, 0.0),
+			shimmer_size: vec2(0.0, 0.0),
+		}
+	}
+}
+
+//anything that can turn the vertex stream from Reactor::vertices into a picture
+//textures are referenced by Tex::Texture index into the atlas, how the atlas gets loaded is up to the backend
+pub trait Renderer {
+	fn resize(&mut self, width: i32, height: i32);
+	fn render(&mut self, verts: Vec<Vertex>, camera: &Camera);
+	fn set_effects(&mut self, _effects: Effects) {}
+}
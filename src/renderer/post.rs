@@ -0,0 +1,119 @@
+use wasm_bindgen::prelude::*;
+use web_sys::{WebGlRenderingContext,WebGlBuffer,WebGlFramebuffer,WebGlTexture,WebGlRenderbuffer};
+use self::WebGlRenderingContext as GL;
+
+use crate::prelude::*;
+use crate::vertex::{Vertex,VertexLayout};
+use super::shader::Program;
+use super::{Camera,Effects};
+
+const SCENE_UNIT: u32 = 1; //texture unit the scene gets bound to, the atlas lives on 0
+
+//the scene gets drawn into a texture, then this draws that texture to the screen with glow and shimmer on top
+pub struct PostProcess {
+	buffer: WebGlBuffer,
+	framebuffer: WebGlFramebuffer,
+	texture: WebGlTexture,
+	depth: WebGlRenderbuffer,
+	size: (i32, i32),
+	time: f32,
+}
+
+impl PostProcess {
+	pub fn new(context: &GL) -> Result<Self, JsValue> {
+		let buffer = context.create_buffer().ok_or("failed to create buffer")?;
+		context.bind_buffer(GL::ARRAY_BUFFER, Some(&buffer));
+		let verts: [f32; 12] = [-1.0, -1.0, 1.0, -1.0, 1.0, 1.0, -1.0, -1.0, -1.0, 1.0, 1.0, 1.0];
+		unsafe { //the view is dropped before anything else allocates
+			context.buffer_data_with_array_buffer_view(GL::ARRAY_BUFFER, &js_sys::Float32Array::view(&verts), GL::STATIC_DRAW);
+		}
+		
+		let texture = context.create_texture().ok_or("failed to create texture")?;
+		context.active_texture(GL::TEXTURE0 + SCENE_UNIT);
+		context.bind_texture(GL::TEXTURE_2D, Some(&texture));
+		context.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MIN_FILTER, GL::LINEAR as i32);
+		context.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MAG_FILTER, GL::LINEAR as i32);
+		context.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_S, GL::CLAMP_TO_EDGE as i32);
+		context.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_T, GL::CLAMP_TO_EDGE as i32);
+		context.active_texture(GL::TEXTURE0);
+		let depth = context.create_renderbuffer().ok_or("failed to create renderbuffer")?;
+		let framebuffer = context.create_framebuffer().ok_or("failed to create framebuffer")?;
+		
+		let mut result = Self {
+			buffer,
+			framebuffer,
+			texture,
+			depth,
+			size: (0, 0),
+			time: 0.0,
+		};
+		result.resize(context, 1, 1)?;
+		Ok(result)
+	}
+	
+	pub fn resize(&mut self, context: &GL, width: i32, height: i32) -> Result<(), JsValue> {
+		let (width, height) = (width.max(1), height.max(1));
+		self.size = (width, height);
+		context.active_texture(GL::TEXTURE0 + SCENE_UNIT);
+		context.bind_texture(GL::TEXTURE_2D, Some(&self.texture));
+		context.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+			GL::TEXTURE_2D,
+			0,
+			GL::RGBA as i32,
+			width,
+			height,
+			0,
+			GL::RGBA,
+			GL::UNSIGNED_BYTE,
+			None,
+		)?;
+		context.active_texture(GL::TEXTURE0);
+		context.bind_renderbuffer(GL::RENDERBUFFER, Some(&self.depth));
+		context.renderbuffer_storage(GL::RENDERBUFFER, GL::DEPTH_COMPONENT16, width, height);
+		context.bind_framebuffer(GL::FRAMEBUFFER, Some(&self.framebuffer));
+		context.framebuffer_texture_2d(GL::FRAMEBUFFER, GL::COLOR_ATTACHMENT0, GL::TEXTURE_2D, Some(&self.texture), 0);
+		context.framebuffer_renderbuffer(GL::FRAMEBUFFER, GL::DEPTH_ATTACHMENT, GL::RENDERBUFFER, Some(&self.depth));
+		let status = context.check_framebuffer_status(GL::FRAMEBUFFER);
+		context.bind_framebuffer(GL::FRAMEBUFFER, None);
+		if status == GL::FRAMEBUFFER_COMPLETE {
+			Ok(())
+		} else {
+			Err(format!("post processing framebuffer incomplete: {}", status).into())
+		}
+	}
+	
+	//everything drawn between begin and finish ends up in the scene texture
+	pub fn begin(&self, context: &GL) {
+		context.bind_framebuffer(GL::FRAMEBUFFER, Some(&self.framebuffer));
+	}
+	
+	//program is the post program from the shader manager, already in use
+	//leaves the post buffer bound and the scene attributes disabled, the caller has to bind its vertex layout again
+	pub fn finish(&mut self, context: &GL, program: &Program, camera: &Camera, effects: &Effects) {
+		self.time = modulus(self.time + 1.0 / 60.0, 1000.0);
+		context.bind_framebuffer(GL::FRAMEBUFFER, None);
+		context.bind_buffer(GL::ARRAY_BUFFER, Some(&self.buffer));
+		let pos = program.attribute("pos").unwrap_or(0);
+		context.vertex_attrib_pointer_with_i32(pos, 2, GL::FLOAT, false, 8, 0);
+		for i in (0..Vertex::ATTRIBUTES.len() as u32).filter(|&i| i != pos) { //scene attributes are bound to locations in ATTRIBUTES order
+			context.disable_vertex_attrib_array(i);
+		}
+		context.active_texture(GL::TEXTURE0 + SCENE_UNIT);
+		context.bind_texture(GL::TEXTURE_2D, Some(&self.texture));
+		context.active_texture(GL::TEXTURE0);
+		
+		//shimmer rect is in world coordinates, move it into uv the same way vs.vs does
+		let to_uv = |p: Vec2<f32>| vec2((p.x + camera.offset.x) * camera.aspect_ratio, p.y + camera.offset.y) * 0.5 + 0.5;
+		let (min, max) = (to_uv(effects.shimmer_pos), to_uv(effects.shimmer_pos + effects.shimmer_size));
+		context.uniform1i(program.uniform("scene"), SCENE_UNIT as i32);
+		context.uniform2f(program.uniform("texel"), 1.0 / self.size.0 as f32, 1.0 / self.size.1 as f32);
+		context.uniform1f(program.uniform("glow"), effects.glow);
+		context.uniform1f(program.uniform("shimmer"), effects.shimmer);
+		context.uniform4f(program.uniform("shimmer_rect"), min.x, min.y, max.x, max.y);
+		context.uniform1f(program.uniform("time"), self.time);
+		
+		context.disable(GL::DEPTH_TEST);
+		context.draw_arrays(GL::TRIANGLES, 0, 6);
+		context.enable(GL::DEPTH_TEST);
+	}
+}
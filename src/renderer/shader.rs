@@ -0,0 +1,149 @@
+use web_sys::{WebGlProgram,WebGlRenderingContext,WebGlShader,WebGlUniformLocation};
+use std::collections::HashMap;
+use self::WebGlRenderingContext as GL;
+
+use crate::error::StartupError;
+
+//a shader file, the name is only used for error messages
+#[derive(Debug,Copy,Clone)]
+pub struct Source {
+	pub name: &'static str,
+	pub text: &'static str,
+}
+
+//a linked program with every active attribute and uniform looked up once at link time
+pub struct Program {
+	program: WebGlProgram,
+	attributes: HashMap<String, u32>,
+	uniforms: HashMap<String, WebGlUniformLocation>,
+}
+
+impl Program {
+	//None if the shader doesn't have it or the compiler optimized it out
+	pub fn attribute(&self, name: &str) -> Option<u32> {
+		self.attributes.get(name).copied()
+	}
+	
+	//None goes straight into uniform* calls, which then do nothing, same as get_uniform_location would
+	pub fn uniform(&self, name: &str) -> Option<&WebGlUniformLocation> {
+		self.uniforms.get(name)
+	}
+}
+
+//every program the renderer knows about, render layers pick one by name
+pub struct Shaders {
+	programs: HashMap<&'static str, Program>,
+	current: Option<&'static str>,
+}
+
+impl Shaders {
+	pub fn new() -> Self {
+		Self {
+			programs: HashMap::new(),
+			current: None,
+		}
+	}
+	
+	//attributes get bound to locations in the order given, so programs sharing a vertex layout agree on where everything is
+	pub fn add(&mut self, context: &GL, name: &'static str, vs: Source, fs: Source, attributes: &[&str]) -> Result<(), StartupError> {
+		let vert_shader = compile_shader(context, GL::VERTEX_SHADER, vs)?;
+		let frag_shader = compile_shader(context, GL::FRAGMENT_SHADER, fs)?;
+		let program = context.create_program().ok_or_else(|| StartupError::Link(format!("{}: unable to create program object", name)))?;
+		context.attach_shader(&program, &vert_shader);
+		context.attach_shader(&program, &frag_shader);
+		for (i, attribute) in attributes.iter().enumerate() {
+			context.bind_attrib_location(&program, i as u32, attribute);
+		}
+		context.link_program(&program);
+		
+		if !context.get_program_parameter(&program, GL::LINK_STATUS).as_bool().unwrap_or(false) {
+			let log = context.get_program_info_log(&program).unwrap_or_else(|| String::from("unknown error"));
+			return Err(StartupError::Link(format!("{}: {}", name, log)));
+		}
+		
+		let mut attributes = HashMap::new();
+		let count = context.get_program_parameter(&program, GL::ACTIVE_ATTRIBUTES).as_f64().unwrap_or(0.0) as u32;
+		for i in 0..count {
+			if let Some(info) = context.get_active_attrib(&program, i) {
+				let location = context.get_attrib_location(&program, &info.name());
+				if location >= 0 {
+					attributes.insert(info.name(), location as u32);
+				}
+			}
+		}
+		let mut uniforms = HashMap::new();
+		let count = context.get_program_parameter(&program, GL::ACTIVE_UNIFORMS).as_f64().unwrap_or(0.0) as u32;
+		for i in 0..count {
+			if let Some(info) = context.get_active_uniform(&program, i) {
+				if let Some(location) = context.get_uniform_location(&program, &info.name()) {
+					uniforms.insert(info.name(), location);
+				}
+			}
+		}
+		
+		if self.current == Some(name) {
+			self.current = None;
+		}
+		self.programs.insert(name, Program { program, attributes, uniforms, });
+		Ok(())
+	}
+	
+	//switches to the named program unless it's already in use
+	pub fn use_program(&mut self, context: &GL, name: &'static str) -> Option<&Program> {
+		let program = self.programs.get(name)?;
+		if self.current != Some(name) {
+			context.use_program(Some(&program.program));
+			self.current = Some(name);
+		}
+		Some(program)
+	}
+}
+
+fn compile_shader(context: &GL, shader_type: u32, source: Source) -> Result<WebGlShader, StartupError> {
+	let shader = context.create_shader(shader_type)
+		.ok_or_else(|| StartupError::Shader { name: source.name, log: String::from("unable to create shader object") })?;
+	context.shader_source(&shader, source.text);
+	context.compile_shader(&shader);
+	
+	if context.get_shader_parameter(&shader, GL::COMPILE_STATUS).as_bool().unwrap_or(false) {
+		Ok(shader)
+	} else {
+		let log = context.get_shader_info_log(&shader).unwrap_or_else(|| String::from("unknown error"));
+		Err(StartupError::Shader { name: source.name, log: annotate(&log, source.text) })
+	}
+}
+
+//info logs look like "ERROR: 0:12: 'foo' : undeclared identifier", this puts the source around each mentioned line under the message
+fn annotate(log: &str, source: &str) -> String {
+	let lines: Vec<&str> = source.lines().collect();
+	let mut result = String::new();
+	for message in log.lines().filter(|l| !l.trim().is_empty() && l.trim() != "\0") {
+		result.push_str(message.trim_end_matches('\0'));
+		result.push('\n');
+		let line = message.split(':').nth(2).and_then(|n| n.trim().parse::<usize>().ok());
+		if let Some(line) = line.filter(|&n| n >= 1 && n <= lines.len()) {
+			for n in line.saturating_sub(1).max(1)..=(line + 1).min(lines.len()) {
+				let marker = if n == line { '>' } else { ' ' };
+				result.push_str(&format!("{} {:4} | {}\n", marker, n, lines[n - 1]));
+			}
+		}
+	}
+	result
+}
+
+#[cfg(test)]
+mod tests {
+	use super::annotate;
+	
+	#[test]
+	fn annotate_shows_source_lines() {
+		let source = "void main() {\n\tfoo = 1.0;\n\tgl_FragColor = vec4(1.0);\n}";
+		let log = "ERROR: 0:2: 'foo' : undeclared identifier\nERROR: 1 compilation errors.  No code generated.\n\0";
+		assert_eq!(annotate(log, source),
+			"ERROR: 0:2: 'foo' : undeclared identifier\n\
+			\x20    1 | void main() {\n\
+			>    2 | \tfoo = 1.0;\n\
+			\x20    3 | \tgl_FragColor = vec4(1.0);\n\
+			ERROR: 1 compilation errors.  No code generated.\n");
+	}
+}
@@ -0,0 +1,235 @@
+use std::path::Path;
+use std::fs::File;
+use std::io::BufWriter;
+
+use crate::vertex::{Vertex,texture_fallback};
+use super::{Renderer,Camera};
+
+//cpu version of the webgl pipeline so frames can be checked without a gpu or browser
+//matches vs.vs, fs.fs and the depth/blend state set up in WebGlRenderer::new
+
+const CLEAR_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+const CLEAR_DEPTH: f32 = 0.0; //clear_depth(-1.1) gets clamped to 0 by gl
+
+pub struct Image {
+	pub width: usize,
+	pub height: usize,
+	pub data: Vec<[f32; 4]>,
+}
+
+impl Image {
+	pub fn new(width: usize, height: usize, col: [f32; 4]) -> Self {
+		Self {
+			width,
+			height,
+			data: vec![col; width * height],
+		}
+	}
+	
+	pub fn load_png<P: AsRef<Path>>(path: P) -> Result<Self, png::DecodingError> {
+		let mut decoder = png::Decoder::new(File::open(path)?);
+		decoder.set_transformations(png::Transformations::EXPAND);
+		let mut reader = decoder.read_info()?;
+		let mut buf = vec![0; reader.output_buffer_size()];
+		let info = reader.next_frame(&mut buf)?;
+		let channels = info.color_type.samples();
+		let data = buf[..info.buffer_size()].chunks(channels).map(|p| match channels {
+			1 => [p[0] as f32 / 255.0, p[0] as f32 / 255.0, p[0] as f32 / 255.0, 1.0],
+			2 => [p[0] as f32 / 255.0, p[0] as f32 / 255.0, p[0] as f32 / 255.0, p[1] as f32 / 255.0],
+			3 => [p[0] as f32 / 255.0, p[1] as f32 / 255.0, p[2] as f32 / 255.0, 1.0],
+			_ => [p[0] as f32 / 255.0, p[1] as f32 / 255.0, p[2] as f32 / 255.0, p[3] as f32 / 255.0],
+		}).collect();
+		Ok(Self {
+			width: info.width as usize,
+			height: info.height as usize,
+			data,
+		})
+	}
+	
+	pub fn save_png<P: AsRef<Path>>(&self, path: P) -> Result<(), png::EncodingError> {
+		let mut encoder = png::Encoder::new(BufWriter::new(File::create(path)?), self.width as u32, self.height as u32);
+		encoder.set_color(png::ColorType::Rgba);
+		encoder.set_depth(png::BitDepth::Eight);
+		let mut writer = encoder.write_header()?;
+		writer.write_image_data(&self.to_rgba8())
+	}
+	
+	pub fn to_rgba8(&self) -> Vec<u8> {
+		self.data.iter().flat_map(|c| c.iter().map(|x| (x.max(0.0).min(1.0) * 255.0).round() as u8)).collect()
+	}
+	
+	//nearest filtering with clamp to edge, flipped vertically like UNPACK_FLIP_Y_WEBGL
+	fn sample(&self, u: f32, v: f32) -> [f32; 4] {
+		let x = ((u * self.width as f32).floor() as isize).max(0).min(self.width as isize - 1) as usize;
+		let y = ((v * self.height as f32).floor() as isize).max(0).min(self.height as isize - 1) as usize;
+		self.data[(self.height - 1 - y) * self.width + x]
+	}
+}
+
+pub struct SoftwareRenderer {
+	pub frame: Image,
+	depth: Vec<f32>,
+	atlas: Option<Image>,
+}
+
+impl SoftwareRenderer {
+	pub fn new(width: usize, height: usize) -> Self {
+		Self {
+			frame: Image::new(width, height, CLEAR_COLOR),
+			depth: vec![CLEAR_DEPTH; width * height],
+			atlas: None,
+		}
+	}
+	
+	pub fn load_atlas<P: AsRef<Path>>(&mut self, path: P) -> Result<(), png::DecodingError> {
+		self.atlas = Some(Image::load_png(path)?);
+		Ok(())
+	}
+	
+	fn draw_triangle(&mut self, tri: &[Vertex], camera: &Camera) {
+		let (w, h) = (self.frame.width as f32, self.frame.height as f32);
+		let mut p = [[0.0; 3]; 3];
+		for (p, v) in p.iter_mut().zip(tri.iter()) {
+			let x = (v.pos.x + camera.offset.x) * camera.aspect_ratio;
+			let y = v.pos.y + camera.offset.y;
+			let z = v.pos.z * 0.0001;
+			*p = [(x + 1.0) / 2.0 * w, (1.0 - y) / 2.0 * h, (z.max(-1.0).min(1.0) + 1.0) / 2.0];
+		}
+		let mut idx = [0, 1, 2];
+		let mut area = edge(p[0], p[1], p[2]);
+		if area == 0.0 {
+			return;
+		} else if area < 0.0 {
+			idx = [0, 2, 1];
+			area = -area;
+		}
+		let (a, b, c) = (p[idx[0]], p[idx[1]], p[idx[2]]);
+		let (va, vb, vc) = (&tri[idx[0]], &tri[idx[1]], &tri[idx[2]]);
+		let min_x = a[0].min(b[0]).min(c[0]).floor().max(0.0) as usize;
+		let min_y = a[1].min(b[1]).min(c[1]).floor().max(0.0) as usize;
+		let max_x = (a[0].max(b[0]).max(c[0]).ceil().max(0.0) as usize).min(self.frame.width);
+		let max_y = (a[1].max(b[1]).max(c[1]).ceil().max(0.0) as usize).min(self.frame.height);
+		for y in min_y..max_y {
+			for x in min_x..max_x {
+				let q = [x as f32 + 0.5, y as f32 + 0.5, 0.0];
+				let w0 = edge(b, c, q);
+				let w1 = edge(c, a, q);
+				let w2 = edge(a, b, q);
+				if !covers(w0, b, c) || !covers(w1, c, a) || !covers(w2, a, b) {
+					continue;
+				}
+				let (w0, w1, w2) = (w0 / area, w1 / area, w2 / area);
+				let i = y * self.frame.width + x;
+				let d = w0 * a[2] + w1 * b[2] + w2 * c[2];
+				if d < self.depth[i] { //depth_func(GEQUAL)
+					continue;
+				}
+				self.depth[i] = d;
+				let lerp = |f: &dyn Fn(&Vertex) -> f32| w0 * f(va) + w1 * f(vb) + w2 * f(vc);
+				let col = [lerp(&|v| v.col[0]), lerp(&|v| v.col[1]), lerp(&|v| v.col[2]), lerp(&|v| v.col[3])];
+				let blend = lerp(&|v| v.blend);
+				let tex = match &self.atlas {
+					Some(atlas) => atlas.sample(lerp(&|v| v.uv.x), lerp(&|v| v.uv.y)),
+					None => [0.0, 0.0, 0.0, 1.0], //texture_fallback means this never gets used
+				};
+				let mut src = [0.0; 4];
+				for (s, (col, tex)) in src.iter_mut().zip(col.iter().zip(tex.iter())) {
+					*s = (1.0 - blend) * col + blend * tex * col;
+				}
+				let alpha = src[3];
+				let src = [src[0] * alpha, src[1] * alpha, src[2] * alpha, alpha];
+				for (dst, src) in self.frame.data[i].iter_mut().zip(src.iter()) { //blend_func(SRC_ALPHA, ONE_MINUS_SRC_ALPHA)
+					*dst = (src * alpha + *dst * (1.0 - alpha)).max(0.0).min(1.0);
+				}
+			}
+		}
+	}
+}
+
+impl Renderer for SoftwareRenderer {
+	fn resize(&mut self, width: i32, height: i32) {
+		let (width, height) = (width.max(0) as usize, height.max(0) as usize);
+		self.frame = Image::new(width, height, CLEAR_COLOR);
+		self.depth = vec![CLEAR_DEPTH; width * height];
+	}
+	
+	fn render(&mut self, mut verts: Vec<Vertex>, camera: &Camera) {
+		if self.atlas.is_none() {
+			texture_fallback(&mut verts);
+		}
+		for c in self.frame.data.iter_mut() {
+			*c = CLEAR_COLOR;
+		}
+		for d in self.depth.iter_mut() {
+			*d = CLEAR_DEPTH;
+		}
+		for tri in verts.chunks_exact(3) {
+			self.draw_triangle(tri, camera);
+		}
+	}
+}
+
+fn edge(a: [f32; 3], b: [f32; 3], c: [f32; 3]) -> f32 {
+	(b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+//top-left fill rule so pixels on an edge shared by two triangles only get drawn once
+fn covers(w: f32, a: [f32; 3], b: [f32; 3]) -> bool {
+	let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+	w > 0.0 || (w == 0.0 && ((dy == 0.0 && dx > 0.0) || dy < 0.0))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::reactor::Reactor;
+	
+	const WIDTH: usize = 800;
+	const HEIGHT: usize = 600;
+	
+	//renders the reactor and compares against tests/golden/<name>.png
+	//run with UPDATE_GOLDEN=1 to (re)record them instead of comparing
+	fn check_golden(name: &str, reactor: &Reactor) {
+		let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+		let mut renderer = SoftwareRenderer::new(WIDTH, HEIGHT);
+		renderer.load_atlas(root.join("textures.png")).expect("failed to load textures.png");
+		let mut camera = Camera::new();
+		camera.aspect_ratio = HEIGHT as f32 / WIDTH as f32;
+		renderer.render(reactor.vertices(), &camera);
+		
+		let out_dir = root.join("target").join("screenshots");
+		std::fs::create_dir_all(&out_dir).unwrap();
+		renderer.frame.save_png(out_dir.join(format!("{}.png", name))).unwrap();
+		
+		let golden_path = root.join("tests").join("golden").join(format!("{}.png", name));
+		if std::env::var_os("UPDATE_GOLDEN").is_some() {
+			std::fs::create_dir_all(golden_path.parent().unwrap()).unwrap();
+			renderer.frame.save_png(&golden_path).unwrap();
+			eprintln!("recorded golden image {}", golden_path.display());
+			return;
+		}
+		assert!(golden_path.exists(), "missing golden image {}, run with UPDATE_GOLDEN=1 to record it", golden_path.display());
+		let golden = Image::load_png(&golden_path).unwrap();
+		assert_eq!((golden.width, golden.height), (WIDTH, HEIGHT), "{} has the wrong size", name);
+		let (actual, expected) = (renderer.frame.to_rgba8(), golden.to_rgba8());
+		let mismatched = actual.chunks(4).zip(expected.chunks(4))
+			.filter(|(a, b)| a.iter().zip(b.iter()).any(|(a, b)| (*a as i32 - *b as i32).abs() > 2))
+			.count();
+		assert!(mismatched == 0, "{} differs from golden in {} pixels, see target/screenshots/{}.png", name, mismatched, name);
+	}
+	
+	#[test]
+	fn golden_initial() {
+		check_golden("initial", &Reactor::new());
+	}
+	
+	#[test]
+	fn golden_water_tank_unlocked() {
+		check_golden("water_tank_unlocked", &Reactor::water_tank_unlocked());
+	}
+	
+	#[test]
+	fn golden_refinery_unlocked() {
+		check_golden("refinery_unlocked", &Reactor::refinery_unlocked());
+	}
+}
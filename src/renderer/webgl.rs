@@ -0,0 +1,287 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{WebGlRenderingContext,HtmlImageElement,WebGlBuffer,WebGlTexture};
+use std::rc::Rc;
+use std::cell::RefCell;
+use self::WebGlRenderingContext as GL;
+
+use crate::vertex::{Vertex,VertexLayout,texture_fallback};
+use crate::error::StartupError;
+use super::{Renderer,Camera,Effects};
+use super::post::PostProcess;
+use super::shader::{Shaders,Program,Source};
+
+const VS: Source = Source { name: "vs.vs", text: include_str!("../vs.vs") };
+const TEXTURED_FS: Source = Source { name: "fs.fs", text: include_str!("../fs.fs") };
+const COLOR_FS: Source = Source { name: "color.fs", text: include_str!("../color.fs") };
+const POST_VS: Source = Source { name: "post.vs", text: include_str!("../post.vs") };
+const POST_FS: Source = Source { name: "post.fs", text: include_str!("../post.fs") };
+
+//every gl object the renderer draws with, all of it is invalid after the context is lost and gets recreated on restore
+struct Resources {
+	shaders: Shaders,
+	buffer: WebGlBuffer,
+	post: Option<PostProcess>,
+}
+
+impl Resources {
+	fn new(context: &GL) -> Result<Self, StartupError> {
+		let attributes: Vec<&str> = Vertex::ATTRIBUTES.iter().map(|a| a.name).collect();
+		let mut shaders = Shaders::new();
+		shaders.add(context, "textured", VS, TEXTURED_FS, &attributes)?;
+		shaders.add(context, "color", VS, COLOR_FS, &attributes)?;
+		
+		let buffer = context.create_buffer().ok_or_else(|| StartupError::Js("failed to create buffer".into()))?;
+		
+		context.clear_color(0.0, 0.0, 0.0, 1.0);
+		context.enable(GL::DEPTH_TEST);
+		context.depth_func(GL::GEQUAL);
+		context.enable(GL::BLEND);
+		context.blend_func(GL::SRC_ALPHA, GL::ONE_MINUS_SRC_ALPHA);
+		
+		let post = shaders.add(context, "post", POST_VS, POST_FS, &["pos"])
+			.and_then(|()| PostProcess::new(context).map_err(StartupError::from));
+		let post = match post {
+			Ok(post) => Some(post),
+			Err(e) => {
+				log!("post processing unavailable: {}", e);
+				None
+			}
+		};
+		context.bind_buffer(GL::ARRAY_BUFFER, Some(&buffer));
+		
+		Ok(Self {
+			shaders,
+			buffer,
+			post,
+		})
+	}
+}
+
+#[derive(Debug,Copy,Clone,PartialEq)]
+enum AtlasState { //sprites are drawn with texture_fallback unless Ready
+	Loading,
+	Ready,
+	Failed,
+}
+
+//the image sticks around after it's uploaded so the texture can be made again after a context restore
+struct Atlas {
+	state: AtlasState,
+	image: Option<HtmlImageElement>,
+	texture: Option<WebGlTexture>,
+}
+
+pub struct WebGlRenderer {
+	context: Rc<GL>,
+	resources: Option<Resources>, //None while the context is lost
+	atlas: Rc<RefCell<Atlas>>,
+	size: (i32, i32),
+	post_enabled: bool,
+	effects: Effects,
+}
+
+impl WebGlRenderer {
+	pub fn new(context: Rc<GL>) -> Result<Self, StartupError> {
+		let resources = Resources::new(&context)?;
+		Ok(Self {
+			context,
+			resources: Some(resources),
+			atlas: Rc::new(RefCell::new(Atlas { state: AtlasState::Loading, image: None, texture: None, })),
+			size: (1, 1),
+			post_enabled: false, //opt in with the p key
+			effects: Effects::none(),
+		})
+	}
+	
+	pub fn toggle_post_processing(&mut self) -> bool {
+		self.post_enabled = !self.post_enabled;
+		self.post_enabled
+	}
+	
+	pub fn load_atlas(&self, src: &str) -> Result<(), StartupError> {
+		let image = HtmlImageElement::new()?;
+		let image2 = image.clone();
+		
+		let context = self.context.clone();
+		let atlas = self.atlas.clone();
+		
+		let onload = Closure::wrap(Box::new(move|| {
+			let mut atlas = atlas.borrow_mut();
+			atlas.image = Some(image2.clone());
+			if !context.is_context_lost() {
+				match upload_atlas(&context, &image2) {
+					Ok(texture) => {
+						atlas.texture = Some(texture);
+						atlas.state = AtlasState::Ready;
+					},
+					Err(e) => {
+						e.show();
+						atlas.state = AtlasState::Failed;
+					},
+				}
+			}
+		}) as Box<dyn Fn()>);
+		
+		let atlas = self.atlas.clone();
+		let src2 = src.to_string();
+		let onerror = Closure::wrap(Box::new(move|| {
+			StartupError::Texture(format!("could not load {}", src2)).show();
+			atlas.borrow_mut().state = AtlasState::Failed;
+		}) as Box<dyn Fn()>);
+		
+		image.set_onload(Some(onload.as_ref().unchecked_ref()));
+		image.set_onerror(Some(onerror.as_ref().unchecked_ref()));
+		image.set_src(src);
+		
+		onload.forget();
+		onerror.forget();
+		Ok(())
+	}
+	
+	//call from the webglcontextlost handler, rendering does nothing until the context is restored
+	pub fn context_lost(&mut self) {
+		log!("webgl context lost");
+		self.resources = None;
+		let mut atlas = self.atlas.borrow_mut();
+		atlas.texture = None;
+		if atlas.state == AtlasState::Ready {
+			atlas.state = AtlasState::Loading;
+		}
+	}
+	
+	//call from the webglcontextrestored handler, recreates everything from scratch
+	pub fn context_restored(&mut self) -> Result<(), StartupError> {
+		log!("webgl context restored");
+		self.resources = Some(Resources::new(&self.context)?);
+		let (w, h) = self.size;
+		self.resize(w, h);
+		let mut atlas = self.atlas.borrow_mut();
+		if let Some(image) = atlas.image.clone() {
+			atlas.texture = Some(upload_atlas(&self.context, &image)?);
+			atlas.state = AtlasState::Ready;
+		}
+		Ok(())
+	}
+}
+
+fn upload_atlas(context: &GL, image: &HtmlImageElement) -> Result<WebGlTexture, StartupError> {
+	let texture = context.create_texture().ok_or_else(|| StartupError::Texture("failed to create texture".into()))?;
+	context.active_texture(GL::TEXTURE0);
+	context.bind_texture(GL::TEXTURE_2D, Some(&texture));
+	context.pixel_storei(GL::UNPACK_FLIP_Y_WEBGL, 1);
+	context.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MIN_FILTER, GL::NEAREST as i32);
+	context.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MAG_FILTER, GL::NEAREST as i32);
+	context.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_S, GL::CLAMP_TO_EDGE as i32);
+	context.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_T, GL::CLAMP_TO_EDGE as i32);
+	//context.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MIN_LOD, 0);
+	//context.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MAX_LOD, 0);
+	//context.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MAX_LEVEL, 0);
+	
+	context.tex_image_2d_with_u32_and_u32_and_image(
+		GL::TEXTURE_2D,
+		0,
+		GL::RGBA as i32,
+		GL::RGBA,
+		GL::UNSIGNED_BYTE,
+		image,
+	).map_err(|e| StartupError::Texture(StartupError::from(e).to_string()))?;
+	Ok(texture)
+}
+
+impl Renderer for WebGlRenderer {
+	fn resize(&mut self, width: i32, height: i32) {
+		self.size = (width, height);
+		let resources = match self.resources.as_mut() {
+			Some(resources) => resources,
+			None => return,
+		};
+		self.context.viewport(0, 0, width, height);
+		if let Some(post) = resources.post.as_mut() {
+			if let Err(e) = post.resize(&self.context, width, height) {
+				log!("disabling post processing: {:?}", e);
+				resources.post = None;
+			}
+		}
+	}
+	
+	fn set_effects(&mut self, effects: Effects) {
+		self.effects = effects;
+	}
+	
+	// Note that `Float32Array::view` is somewhat dangerous (hence the
+	// `unsafe`!). This is creating a raw view into our module's
+	// `WebAssembly.Memory` buffer, but if we allocate more pages for ourself
+	// (aka do a memory allocation in Rust) it'll cause the buffer to change,
+	// causing the `Float32Array` to be invalid.
+	// As a result, after `Float32Array::view` we have to be very careful not to
+	// do any memory allocations before it's dropped.
+	fn render(&mut self, mut verts: Vec<Vertex>, camera: &Camera) {
+		let context = &self.context;
+		let resources = match self.resources.as_mut() {
+			Some(resources) if !context.is_context_lost() => resources,
+			_ => return,
+		};
+		let atlas_ready = self.atlas.borrow().state == AtlasState::Ready;
+		if !atlas_ready {
+			texture_fallback(&mut verts);
+		}
+		let post = if self.post_enabled { resources.post.as_mut() } else { None };
+		if let Some(post) = post.as_ref() {
+			post.begin(context);
+		}
+		//the fallback colors are already baked into the vertices, so skip sampling an atlas that isn't there
+		let layer = if atlas_ready { "textured" } else { "color" };
+		let program = match resources.shaders.use_program(context, layer) {
+			Some(program) => program,
+			None => return,
+		};
+		context.bind_buffer(GL::ARRAY_BUFFER, Some(&resources.buffer));
+		bind_vertex_layout::<Vertex>(context, program);
+		context.active_texture(GL::TEXTURE0);
+		context.bind_texture(GL::TEXTURE_2D, self.atlas.borrow().texture.as_ref());
+		context.uniform1i(program.uniform("tex"), 0);
+		context.uniform2f(program.uniform("offset"), camera.offset.x, camera.offset.y);
+		context.uniform1f(program.uniform("aspect_ratio"), camera.aspect_ratio);
+		context.clear(GL::COLOR_BUFFER_BIT | GL::DEPTH_BUFFER_BIT);
+		context.clear_depth(-1.1);
+		
+		let stride = std::mem::size_of::<Vertex>();
+		let len = verts.len();
+		
+		unsafe {
+			let ptr = verts.as_mut_ptr() as *mut f32; //note that transmuting like this is safe because both Vertex and Vec2/Vec3 are repr(C) so they are just a number of floats anyway
+			let len = len * stride / 4;
+			let cap = verts.capacity() * stride;
+			std::mem::forget(verts);
+			let verts = Vec::from_raw_parts(ptr, len, cap);
+			let verts = js_sys::Float32Array::view(&verts);
+			
+			context.buffer_data_with_array_buffer_view(
+				GL::ARRAY_BUFFER,
+				&verts,
+				GL::STATIC_DRAW,
+			);
+		}
+		
+		context.draw_arrays(GL::TRIANGLES, 0, len as i32);
+		
+		if let Some(post) = post {
+			if let Some(program) = resources.shaders.use_program(context, "post") {
+				post.finish(context, program, camera, &self.effects);
+			}
+		}
+	}
+}
+
+//attribute pointers point at whatever buffer is bound, so redo this after drawing with another buffer
+//attributes the program doesn't use are skipped, the compiler is allowed to drop ones that don't affect the output
+fn bind_vertex_layout<V: VertexLayout>(context: &GL, program: &Program) {
+	let stride = V::stride() as i32;
+	for attribute in V::ATTRIBUTES {
+		if let Some(location) = program.attribute(attribute.name) {
+			context.vertex_attrib_pointer_with_i32(location, attribute.size, GL::FLOAT, false, stride, attribute.offset as i32);
+			context.enable_vertex_attrib_array(location);
+		}
+	}
+}
@@ -1,7 +1,7 @@
 use crate::prelude::*;
 
 const TEXTURE_RES: u32 = 512;
-const TEXTURE_SIZE: Vec2<usize> = Vec2{ x: 2, y: 3 };
+const TEXTURE_SIZE: Vec2<usize> = Vec2{ x: 2, y: 4 };
 
 #[derive(Debug,Copy,Clone)]
 pub enum Tex {
@@ -11,21 +11,71 @@ pub enum Tex {
 
 pub use self::Tex::*;
 
-#[repr(C)]
-#[derive(Debug,Copy,Clone)]
-pub struct Vertex {
-	pos: Vec3<f32>,
-	col: [f32; 4],
-	uv: Vec2<f32>,
-	blend: f32,
+//one float attribute of a vertex type, as the renderer has to describe it to the shader
+#[derive(Debug,Copy,Clone,PartialEq)]
+pub struct Attribute {
+	pub name: &'static str, //name of the attribute in the vertex shader
+	pub size: i32, //in floats
+	pub offset: usize, //in bytes
+}
+
+//describes where each shader attribute lives inside a vertex, implement it with vertex_layout! rather than by hand
+pub trait VertexLayout: Copy {
+	const ATTRIBUTES: &'static [Attribute];
+	
+	fn stride() -> usize {
+		std::mem::size_of::<Self>()
+	}
+}
+
+//declares a repr(C) struct and derives VertexLayout from it, every field is written `field: Type => "shader_attribute"` and must be made of f32s
+macro_rules! vertex_layout {
+	(
+		$(#[$meta:meta])*
+		$vis:vis struct $name:ident {
+			$($field_vis:vis $field:ident: $ty:ty => $attribute:literal),* $(,)?
+		}
+	) => {
+		$(#[$meta])*
+		#[repr(C)]
+		$vis struct $name {
+			$($field_vis $field: $ty,)*
+		}
+		
+		impl VertexLayout for $name {
+			const ATTRIBUTES: &'static [Attribute] = &[
+				$(Attribute {
+					name: $attribute,
+					size: (std::mem::size_of::<$ty>() / std::mem::size_of::<f32>()) as i32,
+					offset: std::mem::offset_of!($name, $field),
+				},)*
+			];
+		}
+	};
 }
 
-pub fn quad_uvs(n: usize) -> [Vec2<f32>; 6] { //TODO: does 
+vertex_layout! {
+	#[derive(Debug,Copy,Clone)]
+	pub struct Vertex {
+		pub pos: Vec3<f32> => "pos",
+		pub col: [f32; 4] => "vcol",
+		pub uv: Vec2<f32> => "vuv",
+		pub blend: f32 => "vblend",
+	}
+}
+
+fn sprite_rect(n: usize) -> (Vec2<f32>, Vec2<f32>) { //bottom left uv and uv size of sprite n
 	let (w, h) = (1.0 / TEXTURE_SIZE.x as f32, 1.0 / TEXTURE_SIZE.y as f32);
 	let zz = vec2((n % TEXTURE_SIZE.x) as f32 / TEXTURE_SIZE.x as f32, 1.0 - h - (n / TEXTURE_SIZE.x) as f32 / TEXTURE_SIZE.y as f32);
 	let zz = vec2(zz.x + 2.5 / TEXTURE_RES as f32, zz.y + 2.5 / TEXTURE_RES as f32); //pixel correction
 	let (w, h) = (w - 5.0 / TEXTURE_RES as f32, h - 5.0 / TEXTURE_RES as f32); //more pixel correction
 	//why does pixel correct require 5 times the normal amount?? are mipmaps not properly disabled?
+	(zz, vec2(w, h))
+}
+
+pub fn quad_uvs(n: usize) -> [Vec2<f32>; 6] {
+	let (zz, size) = sprite_rect(n);
+	let (w, h) = (size.x, size.y);
 	[
 		zz,
 		zz + vec2(w, 0.0),
@@ -36,6 +86,33 @@ pub fn quad_uvs(n: usize) -> [Vec2<f32>; 6] { //TODO: does
 	]
 }
 
+pub fn texture_index(uv: Vec2<f32>) -> usize { //inverse of quad_uvs, works for any uv inside a sprite
+	let x = (uv.x * TEXTURE_SIZE.x as f32).floor() as usize;
+	let y = ((1.0 - uv.y) * TEXTURE_SIZE.y as f32).floor() as usize;
+	x + y * TEXTURE_SIZE.x
+}
+
+//stand in for each sprite while the atlas isn't loaded, these get tinted the same way the sprite would
+const FALLBACK_COLORS: [[f32; 4]; 8] = [
+	[1.0, 0.55, 0.1, 1.0], //ignite
+	[0.3, 0.3, 0.3, 1.0], //vent closed
+	[0.6, 0.6, 0.6, 1.0], //vent open
+	[0.35, 0.22, 0.15, 1.0], //dial
+	[1.0, 0.0, 0.0, 1.0], //blocked
+	[0.0; 4], //fuel valve closed, empty in the atlas too so only the control backing shows
+	[0.0; 4], //fuel valve open, also empty in the atlas
+	[1.0; 4], //panel, just the tint
+];
+
+//turns textured vertices into flat colored ones, for drawing without the atlas
+pub fn texture_fallback(v: &mut [Vertex]) {
+	for vert in v.iter_mut().filter(|v| v.blend > 0.0) {
+		let c = FALLBACK_COLORS.get(texture_index(vert.uv)).cloned().unwrap_or([1.0, 0.0, 1.0, 1.0]);
+		vert.col = [c[0] * vert.col[0], c[1] * vert.col[1], c[2] * vert.col[2], c[3] * vert.col[3]];
+		vert.blend = 0.0;
+	}
+}
+
 pub fn make_quad(pos: Vec3<f32>, mut size: Vec2<f32>, tex: Tex, trans: Mat2<f32>) -> [Vertex; 6] {
 	let z = pos.z;
 	let mut pos = vec2(pos.x, pos.y);
@@ -53,6 +130,7 @@ pub fn make_quad(pos: Vec3<f32>, mut size: Vec2<f32>, tex: Tex, trans: Mat2<f32>
 	match tex {
 		Color(rgb) => col = rgb,
 		Texture(n) => {
+			col = [1.0; 4]; //no tint
 			uvs = quad_uvs(n);
 			blend = 1.0;
 		}
@@ -88,3 +166,291 @@ pub fn quadify(v: [Vertex; 4]) -> [Vertex; 6] { //assumes v.x is opposite to v[3
 	]
 }
 
+
+const NINE_SLICE_EDGE: f32 = 0.25; //fraction of a sprite taken up by each border in the atlas
+
+//draws sprite n stretched over any size without stretching its corners, border is how big the corners are drawn
+//tint gets multiplied with the sprite, so grey sprites can be recolored
+pub fn nine_slice(v: &mut Vec<Vertex>, pos: Vec3<f32>, size: Vec2<f32>, border: f32, n: usize, tint: [f32; 4]) {
+	let border = border.min(size.x.abs() / 2.0).min(size.y.abs() / 2.0);
+	let (x, y) = (pos.x.min(pos.x + size.x), pos.y.min(pos.y + size.y));
+	let (w, h) = (size.x.abs(), size.y.abs());
+	let xs = [x, x + border, x + w - border, x + w];
+	let ys = [y, y + border, y + h - border, y + h];
+	let (zz, uv_size) = sprite_rect(n);
+	let us = [zz.x, zz.x + uv_size.x * NINE_SLICE_EDGE, zz.x + uv_size.x * (1.0 - NINE_SLICE_EDGE), zz.x + uv_size.x];
+	let vs = [zz.y, zz.y + uv_size.y * NINE_SLICE_EDGE, zz.y + uv_size.y * (1.0 - NINE_SLICE_EDGE), zz.y + uv_size.y];
+	let corner = |i: usize, j: usize| Vertex { pos: vec3(xs[i], ys[j], pos.z), col: tint, uv: vec2(us[i], vs[j]), blend: 1.0, };
+	for i in 0..3 {
+		for j in 0..3 {
+			v.extend_from_slice(&quadify([corner(i, j), corner(i + 1, j), corner(i, j + 1), corner(i + 1, j + 1)]));
+		}
+	}
+}
+
+//vector shapes, these are all flat colored and only emit plain triangles so they work with the existing shader
+
+fn vert(pos: Vec2<f32>, z: f32, col: [f32; 4]) -> Vertex {
+	Vertex { pos: pos.extend(z), col, uv: Vec2::zero(), blend: 0.0, }
+}
+
+fn segments(radius: f32, sweep: f32) -> usize { //roughly one segment per 0.01 units of arc length
+	((radius * sweep.abs() / 0.01).ceil() as usize).max(3).min(128)
+}
+
+fn unit(theta: f32) -> Vec2<f32> {
+	vec2(theta.cos(), theta.sin())
+}
+
+pub fn arc(v: &mut Vec<Vertex>, center: Vec3<f32>, radius: f32, start: f32, sweep: f32, col: [f32; 4]) { //filled pie slice, angles in radians counterclockwise from +x
+	let c = vec2(center.x, center.y);
+	let n = segments(radius, sweep);
+	for i in 0..n {
+		let a = start + sweep * i as f32 / n as f32;
+		let b = start + sweep * (i + 1) as f32 / n as f32;
+		v.push(vert(c, center.z, col));
+		v.push(vert(c + unit(a) * radius, center.z, col));
+		v.push(vert(c + unit(b) * radius, center.z, col));
+	}
+}
+
+pub fn filled_circle(v: &mut Vec<Vertex>, center: Vec3<f32>, radius: f32, col: [f32; 4]) {
+	arc(v, center, radius, 0.0, 2.0 * PI, col);
+}
+
+pub fn stroked_arc(v: &mut Vec<Vertex>, center: Vec3<f32>, radius: f32, width: f32, start: f32, sweep: f32, col: [f32; 4]) { //width is centered on radius
+	let c = vec2(center.x, center.y);
+	let (inner, outer) = ((radius - width / 2.0).max(0.0), radius + width / 2.0);
+	let n = segments(outer, sweep);
+	for i in 0..n {
+		let a = unit(start + sweep * i as f32 / n as f32);
+		let b = unit(start + sweep * (i + 1) as f32 / n as f32);
+		v.extend_from_slice(&quadify([
+			vert(c + a * inner, center.z, col),
+			vert(c + a * outer, center.z, col),
+			vert(c + b * inner, center.z, col),
+			vert(c + b * outer, center.z, col),
+		]));
+	}
+}
+
+pub fn ring(v: &mut Vec<Vertex>, center: Vec3<f32>, radius: f32, width: f32, col: [f32; 4]) {
+	stroked_arc(v, center, radius, width, 0.0, 2.0 * PI, col);
+}
+
+pub fn rounded_rect(v: &mut Vec<Vertex>, pos: Vec3<f32>, size: Vec2<f32>, radius: f32, col: [f32; 4]) { //pos is the bottom left corner like quad
+	let r = radius.max(0.0).min(size.x.abs() / 2.0).min(size.y.abs() / 2.0);
+	let (x, y, z) = (pos.x.min(pos.x + size.x), pos.y.min(pos.y + size.y), pos.z);
+	let (w, h) = (size.x.abs(), size.y.abs());
+	quad(v, vec3(x, y + r, z), vec2(w, h - 2.0 * r), Color(col));
+	quad(v, vec3(x + r, y, z), vec2(w - 2.0 * r, r), Color(col));
+	quad(v, vec3(x + r, y + h - r, z), vec2(w - 2.0 * r, r), Color(col));
+	if r > 0.0 {
+		arc(v, vec3(x + w - r, y + h - r, z), r, 0.0, PI / 2.0, col);
+		arc(v, vec3(x + r, y + h - r, z), r, PI / 2.0, PI / 2.0, col);
+		arc(v, vec3(x + r, y + r, z), r, PI, PI / 2.0, col);
+		arc(v, vec3(x + w - r, y + r, z), r, PI * 1.5, PI / 2.0, col);
+	}
+}
+
+fn normal(a: Vec2<f32>, b: Vec2<f32>) -> Vec2<f32> {
+	let d = b - a;
+	let len = (d.x * d.x + d.y * d.y).sqrt();
+	if len == 0.0 { Vec2::zero() } else { vec2(-d.y / len, d.x / len) }
+}
+
+#[cfg_attr(not(test), allow(dead_code))] //nothing draws lines yet, kept with the other shapes so they're there when a graph needs one
+pub fn polyline(v: &mut Vec<Vertex>, points: &[Vec2<f32>], z: f32, width: f32, col: [f32; 4]) { //thick line through points with mitered joins, nothing overlaps so transparent lines blend evenly
+	if points.len() < 2 {
+		return;
+	}
+	let half = width / 2.0;
+	let offsets: Vec<Vec2<f32>> = (0..points.len()).map(|i| {
+		let prev = if i > 0 { normal(points[i - 1], points[i]) } else { normal(points[i], points[i + 1]) };
+		let next = if i + 1 < points.len() { normal(points[i], points[i + 1]) } else { prev };
+		let m = prev + next;
+		let len = (m.x * m.x + m.y * m.y).sqrt();
+		if len < 0.0001 { //line doubles back on itself
+			return prev * half;
+		}
+		let m = m / len;
+		let cos = m.x * prev.x + m.y * prev.y;
+		m * (half / cos).min(half * 4.0) //miter limit
+	}).collect();
+	for i in 0..points.len() - 1 {
+		v.extend_from_slice(&quadify([
+			vert(points[i] - offsets[i], z, col),
+			vert(points[i] + offsets[i], z, col),
+			vert(points[i + 1] - offsets[i + 1], z, col),
+			vert(points[i + 1] + offsets[i + 1], z, col),
+		]));
+	}
+}
+
+//3x5 pixel font, one row per entry from the top, 0b100 is the left column
+//lowercase draws as uppercase, anything missing draws as a space
+fn glyph(c: char) -> [u8; 5] {
+	match c.to_ascii_uppercase() {
+		'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+		'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+		'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+		'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+		'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+		'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+		'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+		'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+		'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+		'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+		'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+		'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+		'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+		'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+		'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+		'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+		'Q' => [0b010, 0b101, 0b101, 0b110, 0b011],
+		'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+		'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+		'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+		'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+		'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+		'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+		'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+		'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+		'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+		'0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+		'1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+		'2' => [0b110, 0b001, 0b010, 0b100, 0b111],
+		'3' => [0b110, 0b001, 0b010, 0b001, 0b110],
+		'4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+		'5' => [0b111, 0b100, 0b110, 0b001, 0b110],
+		'6' => [0b011, 0b100, 0b111, 0b101, 0b111],
+		'7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+		'8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+		'9' => [0b111, 0b101, 0b111, 0b001, 0b110],
+		'.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+		':' => [0b000, 0b010, 0b000, 0b010, 0b000],
+		'-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+		'+' => [0b000, 0b010, 0b111, 0b010, 0b000],
+		'%' => [0b101, 0b001, 0b010, 0b100, 0b101],
+		'/' => [0b001, 0b001, 0b010, 0b100, 0b100],
+		'!' => [0b010, 0b010, 0b010, 0b000, 0b010],
+		_ => [0; 5],
+	}
+}
+
+pub fn text_width(height: f32, s: &str) -> f32 { //every character is 3 pixels wide with a 1 pixel gap, the last gap isn't counted
+	let n = s.chars().count() as f32;
+	(n * 4.0 - 1.0).max(0.0) * height / 5.0
+}
+
+pub fn text(v: &mut Vec<Vertex>, pos: Vec3<f32>, height: f32, s: &str, col: [f32; 4]) { //pos is the bottom left corner, each run of lit pixels in a row is one quad
+	let pixel = height / 5.0;
+	for (i, c) in s.chars().enumerate() {
+		let x = pos.x + i as f32 * 4.0 * pixel;
+		for (row, bits) in glyph(c).iter().enumerate() {
+			let y = pos.y + (4 - row) as f32 * pixel;
+			let mut col_start = None;
+			for column in 0..4 {
+				let lit = column < 3 && bits & (0b100 >> column) != 0;
+				match (lit, col_start) {
+					(true, None) => col_start = Some(column),
+					(false, Some(start)) => {
+						quad(v, vec3(x + start as f32 * pixel, y, pos.z), vec2((column - start) as f32 * pixel, pixel), Color(col));
+						col_start = None;
+					},
+					_ => {},
+				}
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::mem::MaybeUninit;
+	use std::ptr::addr_of;
+	
+	#[test]
+	fn vertex_layout_offsets() {
+		let v = MaybeUninit::<Vertex>::uninit();
+		let base = v.as_ptr();
+		let offset = |field: *const u8| field as usize - base as usize;
+		let expected = unsafe { [ //addr_of never reads the uninitialized fields
+			("pos", 3, offset(addr_of!((*base).pos) as *const u8)),
+			("vcol", 4, offset(addr_of!((*base).col) as *const u8)),
+			("vuv", 2, offset(addr_of!((*base).uv) as *const u8)),
+			("vblend", 1, offset(addr_of!((*base).blend) as *const u8)),
+		] };
+		let actual: Vec<_> = Vertex::ATTRIBUTES.iter().map(|a| (a.name, a.size, a.offset)).collect();
+		assert_eq!(actual, expected);
+		assert_eq!(Vertex::stride(), 40);
+		let end = Vertex::ATTRIBUTES.iter().map(|a| a.offset + a.size as usize * 4).max().unwrap();
+		assert_eq!(end, Vertex::stride());
+	}
+	
+	fn distance(v: &Vertex, center: Vec2<f32>) -> f32 {
+		let d = vec2(v.pos.x, v.pos.y) - center;
+		(d.x * d.x + d.y * d.y).sqrt()
+	}
+	
+	#[test]
+	fn arc_triangle_counts() {
+		let mut v = Vec::new();
+		arc(&mut v, vec3(0.0, 0.0, 1.0), 0.1, 0.0, PI / 2.0, [1.0; 4]);
+		assert_eq!(v.len(), 16 * 3); //0.157 of arc length at 0.01 per segment
+		v.clear();
+		filled_circle(&mut v, vec3(0.0, 0.0, 1.0), 10.0, [1.0; 4]);
+		assert_eq!(v.len(), 128 * 3);
+		v.clear();
+		filled_circle(&mut v, vec3(0.0, 0.0, 1.0), 0.0001, [1.0; 4]);
+		assert_eq!(v.len(), 3 * 3);
+	}
+	
+	#[test]
+	fn arc_vertices_on_radius() {
+		let mut v = Vec::new();
+		let center = vec2(0.3, -0.2);
+		arc(&mut v, center.extend(1.0), 0.25, 0.5, 2.0, [1.0; 4]);
+		for tri in v.chunks(3) {
+			assert!(distance(&tri[0], center) < 1e-6);
+			assert!((distance(&tri[1], center) - 0.25).abs() < 1e-5);
+			assert!((distance(&tri[2], center) - 0.25).abs() < 1e-5);
+		}
+		v.clear();
+		ring(&mut v, center.extend(1.0), 0.25, 0.1, [1.0; 4]);
+		assert_eq!(v.len() % 6, 0);
+		for vert in v.iter() {
+			let d = distance(vert, center);
+			assert!((d - 0.2).abs() < 1e-5 || (d - 0.3).abs() < 1e-5, "{} is on neither edge of the ring", d);
+		}
+	}
+	
+	#[test]
+	fn rounded_rect_stays_in_bounds() {
+		let mut v = Vec::new();
+		rounded_rect(&mut v, vec3(0.1, 0.2, 1.0), vec2(0.5, 0.3), 0.05, [1.0; 4]);
+		let (mut min, mut max) = (vec2(std::f32::MAX, std::f32::MAX), vec2(std::f32::MIN, std::f32::MIN));
+		for vert in v.iter() {
+			min = vec2(min.x.min(vert.pos.x), min.y.min(vert.pos.y));
+			max = vec2(max.x.max(vert.pos.x), max.y.max(vert.pos.y));
+		}
+		assert!((min.x - 0.1).abs() < 1e-5 && (min.y - 0.2).abs() < 1e-5);
+		assert!((max.x - 0.6).abs() < 1e-5 && (max.y - 0.5).abs() < 1e-5);
+		let corner = v.iter().any(|vert| (vert.pos.x - 0.1).abs() < 1e-5 && (vert.pos.y - 0.2).abs() < 1e-5);
+		assert!(!corner, "the corner should be rounded off");
+	}
+	
+	#[test]
+	fn polyline_miters_right_angle() {
+		let mut v = Vec::new();
+		polyline(&mut v, &[vec2(0.0, 0.0), vec2(1.0, 0.0), vec2(1.0, 1.0)], 1.0, 0.2, [1.0; 4]);
+		assert_eq!(v.len(), 2 * 6);
+		let close = |vert: &Vertex, x: f32, y: f32| (vert.pos.x - x).abs() < 1e-5 && (vert.pos.y - y).abs() < 1e-5;
+		//the join is offset diagonally so both segments keep their full width
+		assert!(close(&v[2], 0.9, 0.1));
+		assert!(close(&v[4], 1.1, -0.1));
+		assert!(close(&v[6], 1.1, -0.1));
+		assert!(close(&v[7], 0.9, 0.1));
+	}
+}